@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::document::{normalize_id, split_revision};
+use crate::errors::{Error, Result};
+
+pub const ANNOTATIONS: &str = "annotations";
+pub const ACTIONS: &[&str] = &["add", "rm", "list"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotation {
+    pub starred: bool,
+    pub tags: BTreeSet<String>,
+    pub notes: Vec<String>,
+}
+
+impl Annotation {
+    fn is_empty(&self) -> bool {
+        !self.starred && self.tags.is_empty() && self.notes.is_empty()
+    }
+
+    pub fn fmt_tags(&self) -> String {
+        self.tags
+            .iter()
+            .map(|tag| format!("#{}", tag))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn fmt_summary(&self) -> String {
+        let mut lines = Vec::new();
+        if self.starred {
+            lines.push("Starred".to_string());
+        }
+        if !self.tags.is_empty() {
+            lines.push(format!("Tags: {}", self.fmt_tags()));
+        }
+        for (i, note) in self.notes.iter().enumerate() {
+            lines.push(format!("Note {}: {}", i + 1, note));
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Annotations {
+    path: PathBuf,
+    map: BTreeMap<String, Annotation>,
+}
+
+impl Annotations {
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let path = dir.join(ANNOTATIONS);
+        let map = match fs::read_to_string(&path) {
+            Ok(content) => parse(&content)?,
+            Err(_) if !path.exists() => BTreeMap::new(),
+            Err(e) => {
+                return Err(Error::AnnotationError(format!(
+                    "Failed to read '{}': {}",
+                    path.to_string_lossy(),
+                    e
+                )))
+            }
+        };
+        Ok(Annotations { path, map })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Annotation> {
+        self.map.get(&key(id))
+    }
+
+    pub fn entry(&mut self, id: &str) -> &mut Annotation {
+        self.map.entry(key(id)).or_default()
+    }
+
+    pub fn tags(&self) -> BTreeMap<&String, usize> {
+        let mut tags = BTreeMap::new();
+        for annotation in self.map.values() {
+            for tag in &annotation.tags {
+                *tags.entry(tag).or_default() += 1;
+            }
+        }
+        tags
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Annotation)> {
+        self.map.iter()
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        self.map.retain(|_, annotation| !annotation.is_empty());
+        let mut lines = Vec::new();
+        for (id, annotation) in &self.map {
+            if annotation.starred {
+                lines.push(format!("{} star", id));
+            }
+            for tag in &annotation.tags {
+                lines.push(format!("{} tag {}", id, tag));
+            }
+            for note in &annotation.notes {
+                lines.push(format!("{} note {}", id, note.replace('\n', " ")));
+            }
+        }
+        let tmp = self.path.with_extension("tmp");
+        let content = lines
+            .into_iter()
+            .map(|line| line + "\n")
+            .collect::<String>();
+        match fs::write(&tmp, content).and_then(|_| fs::rename(&tmp, &self.path)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(Error::AnnotationError(format!(
+                "Failed to write '{}': {}",
+                self.path.to_string_lossy(),
+                e
+            ))),
+        }
+    }
+}
+
+fn key(id: &str) -> String {
+    split_revision(&normalize_id(id)).0.to_string()
+}
+
+fn parse(content: &str) -> Result<BTreeMap<String, Annotation>> {
+    let mut map: BTreeMap<String, Annotation> = BTreeMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, ' ');
+        let id = key(fields.next().unwrap());
+        let annotation = map.entry(id).or_default();
+        match (fields.next(), fields.next().map(|value| value.trim())) {
+            (Some("star"), None) => annotation.starred = true,
+            (Some("tag"), Some(tag)) if !tag.is_empty() => {
+                annotation.tags.insert(tag.to_string());
+            }
+            (Some("note"), Some(note)) if !note.is_empty() => {
+                annotation.notes.push(note.to_string())
+            }
+            _ => {
+                return Err(Error::AnnotationError(format!(
+                    "Expected '<id> star', '<id> tag <tag>' or '<id> note <text>' on line {} of annotations",
+                    i + 1
+                )))
+            }
+        }
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::scratch_dir;
+
+    #[test]
+    fn test_parse_annotations() -> Result<()> {
+        let map = parse(
+            "# personal annotations\n\
+             rfc6468 star\n\
+             RFC6468 tag sieve\n\
+             rfc6468 tag sip\n\
+             rfc8174 note Read alongside RFC 2119\n",
+        )?;
+        let rfc6468 = &map["rfc6468"];
+        assert!(rfc6468.starred);
+        assert_eq!(rfc6468.fmt_tags(), "#sieve #sip");
+        assert_eq!(map["rfc8174"].notes, vec!["Read alongside RFC 2119"]);
+        assert!(parse("rfc6468 bogus\n").is_err());
+        assert!(parse("rfc6468 tag\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_annotations() -> Result<()> {
+        let dir = scratch_dir("annotations");
+        let mut annotations = Annotations::from_dir(&dir)?;
+        assert!(annotations.get("rfc6468").is_none());
+        annotations
+            .entry("rfc6468")
+            .tags
+            .insert("sieve".to_string());
+        annotations.entry("RFC 8174").starred = true;
+        annotations
+            .entry("rfc8174")
+            .notes
+            .push("two\nlines".to_string());
+        annotations.entry("rfc2119").starred = false;
+        annotations
+            .entry("draft-ietf-sidrops-rpkimaxlen-05")
+            .tags
+            .insert("rpki".to_string());
+        annotations.save()?;
+        assert_eq!(
+            fs::read_to_string(dir.join(ANNOTATIONS)).unwrap(),
+            "draft-ietf-sidrops-rpkimaxlen tag rpki\nrfc6468 tag sieve\nrfc8174 star\nrfc8174 note two lines\n"
+        );
+        let annotations = Annotations::from_dir(&dir)?;
+        assert_eq!(annotations.tags().len(), 2);
+        assert!(annotations
+            .get("draft-ietf-sidrops-rpkimaxlen-04")
+            .is_some());
+        assert_eq!(
+            annotations.get("rfc8174").unwrap().fmt_summary(),
+            "Starred\nNote 1: two lines"
+        );
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use crate::collection::Collection;
+use crate::document::Document;
+
+#[derive(Debug, Clone)]
+pub struct Author {
+    pub fullname: String,
+    pub given: String,
+    pub family: String,
+    pub email: Option<String>,
+}
+
+impl Author {
+    pub fn parse(creator: &str) -> Self {
+        let (name, email) = match creator.split_once('<') {
+            Some((name, email)) => (
+                name.trim(),
+                Some(email.trim_end_matches('>').trim().to_lowercase()),
+            ),
+            None => (creator.trim(), None),
+        };
+        let (given, family) = match name.split_once(',') {
+            Some((family, given)) => (given.trim(), family.trim()),
+            None => match name.rsplit_once(' ') {
+                Some((given, family)) => (given.trim(), family.trim()),
+                None => ("", name),
+            },
+        };
+        let fullname = if given.is_empty() {
+            family.to_string()
+        } else {
+            format!("{} {}", given, family)
+        };
+        Author {
+            fullname,
+            given: given.to_string(),
+            family: family.to_string(),
+            email,
+        }
+    }
+
+    pub fn initials(&self) -> String {
+        self.given
+            .split(|c: char| c.is_whitespace() || c == '.')
+            .filter_map(|name| name.chars().next())
+            .map(|c| format!("{}.", c))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn initials_surname(&self) -> String {
+        match self.initials().as_str() {
+            "" => self.family.to_string(),
+            initials => format!("{} {}", initials, self.family),
+        }
+    }
+
+    pub fn key(&self) -> String {
+        fold(&self.fullname)
+    }
+
+    fn is_abbreviated(&self) -> bool {
+        !self.given.is_empty()
+            && self
+                .given
+                .split(|c: char| c.is_whitespace() || c == '.')
+                .all(|name| name.chars().count() <= 1)
+    }
+
+    fn sort_key(&self) -> (String, String) {
+        (fold(&self.family), fold(&self.given))
+    }
+}
+
+#[derive(Debug)]
+pub struct AuthorEntry {
+    pub author: Author,
+    pub docs: Vec<Document>,
+}
+
+pub struct AuthorIndex(Vec<AuthorEntry>);
+
+impl AuthorIndex {
+    pub fn new(collection: Collection, jobs: usize) -> Self {
+        AuthorIndex::from(collection.newest(1).with_meta(jobs).with_lineage().active())
+    }
+
+    pub fn from(docs: Collection) -> Self {
+        let mut entries: HashMap<String, AuthorEntry> = HashMap::new();
+        for doc in docs {
+            let creators = match doc.meta() {
+                Ok(meta) => meta.values("Creator"),
+                Err(_) => continue,
+            };
+            for creator in creators {
+                let author = Author::parse(creator);
+                let entry = entries.entry(author.key()).or_insert_with(|| AuthorEntry {
+                    author: author.clone(),
+                    docs: Vec::new(),
+                });
+                if entry.author.email.is_none() {
+                    entry.author.email = author.email;
+                }
+                if !entry.docs.iter().any(|other| other.id() == doc.id()) {
+                    entry.docs.push(doc.clone());
+                }
+            }
+        }
+        let abbreviated = entries
+            .iter()
+            .filter(|(_, entry)| entry.author.is_abbreviated())
+            .map(|(key, _)| key.to_owned())
+            .collect::<Vec<String>>();
+        for key in abbreviated {
+            let author = &entries[&key].author;
+            let candidates = entries
+                .iter()
+                .filter(|(_, other)| {
+                    !other.author.is_abbreviated()
+                        && other.author.sort_key().0 == author.sort_key().0
+                        && other.author.initials() == author.initials()
+                })
+                .map(|(key, _)| key.to_owned())
+                .collect::<Vec<String>>();
+            if let [target] = candidates.as_slice() {
+                let entry = entries.remove(&key).unwrap();
+                let target = entries.get_mut(target).unwrap();
+                for doc in entry.docs {
+                    if !target.docs.iter().any(|other| other.id() == doc.id()) {
+                        target.docs.push(doc);
+                    }
+                }
+            }
+        }
+        let mut entries = entries.into_values().collect::<Vec<AuthorEntry>>();
+        for entry in entries.iter_mut() {
+            entry.docs.sort_by(|a, b| {
+                (a.id().starts_with("draft"), a.id()).cmp(&(b.id().starts_with("draft"), b.id()))
+            });
+        }
+        entries.sort_by_cached_key(|entry| entry.author.sort_key());
+        AuthorIndex(entries)
+    }
+
+    pub fn find(&self, name: &str) -> Vec<&AuthorEntry> {
+        let query = Author::parse(name);
+        let key = query.key();
+        let exact = self
+            .0
+            .iter()
+            .filter(|entry| entry.author.key() == key)
+            .collect::<Vec<&AuthorEntry>>();
+        if !exact.is_empty() {
+            return exact;
+        }
+        self.0
+            .iter()
+            .filter(|entry| {
+                let words = entry.author.key();
+                let words = words.split(' ').collect::<Vec<&str>>();
+                key.split(' ').all(|word| words.contains(&word))
+                    || (query.is_abbreviated()
+                        && entry.author.sort_key().0 == query.sort_key().0
+                        && entry.author.initials() == query.initials())
+            })
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a AuthorIndex {
+    type Item = &'a AuthorEntry;
+    type IntoIter = std::slice::Iter<'a, AuthorEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+fn fold(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '.')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::errors::Result;
+    use crate::test::resource_path;
+
+    #[test]
+    fn test_parse_author() {
+        for creator in &[
+            "Alexey Melnikov <alexey.melnikov@isode.com>",
+            "Melnikov, Alexey",
+            "MELNIKOV, Alexey",
+        ] {
+            let author = Author::parse(creator);
+            assert_eq!(author.key(), "alexey melnikov");
+            assert_eq!(author.family.to_lowercase(), "melnikov");
+            assert_eq!(author.initials(), "A.");
+        }
+        let author = Author::parse("Alexey Melnikov <Alexey.Melnikov@isode.com>");
+        assert_eq!(author.email.as_deref(), Some("alexey.melnikov@isode.com"));
+        assert_eq!(Author::parse("K. Li").initials_surname(), "K. Li");
+        assert_eq!(Author::parse("K. Li").key(), "k li");
+        assert!(Author::parse("K. Li").is_abbreviated());
+        assert!(!Author::parse("Kepeng Li").is_abbreviated());
+    }
+
+    #[test]
+    fn test_author_index() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let index = AuthorIndex::new(collection, 1);
+        let snijders = index.find("snijders, job");
+        assert_eq!(snijders.len(), 1);
+        assert_eq!(
+            snijders[0]
+                .docs
+                .iter()
+                .map(|doc| doc.id().as_str())
+                .collect::<Vec<&str>>(),
+            vec!["rfc8212"]
+        );
+        let leiba = index.find("Leiba");
+        assert_eq!(leiba.len(), 1);
+        assert_eq!(leiba[0].author.fullname, "Barry Leiba");
+        assert_eq!(index.find("B. Leiba")[0].author.fullname, "Barry Leiba");
+        assert!(index.find("nobody").is_empty());
+        // Documents with malformed metadata are skipped rather than panicking.
+        let index = AuthorIndex::from(Collection::from_dir(resource_path(""))?);
+        assert_eq!(index.find("snijders, job").len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+
+use pipeliner::Pipeline;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::collection::Collection;
+use crate::document::Document;
+use crate::errors::{Error, Result};
+use crate::history::History;
+
+const INDEX_HELP: &str = " [enter] read  [up/down] select  [esc] quit ";
+
+const READER_HELP: &str = " [r] references  [b] back  [up/down/pgup/pgdn] scroll  [esc] index ";
+
+const REFERENCES_HELP: &str = " [enter] follow  [esc] reader ";
+
+const PAGE: u16 = 20;
+
+struct Entry {
+    doc: Document,
+    line: String,
+}
+
+enum Mode {
+    Index,
+    Reader,
+    References,
+}
+
+struct Reader {
+    entry: usize,
+    body: String,
+    scroll: u16,
+    references: Vec<usize>,
+    selected: ListState,
+}
+
+pub struct Browser {
+    entries: Vec<Entry>,
+    ids: HashMap<String, usize>,
+    query: String,
+    matches: Vec<usize>,
+    selected: ListState,
+    mode: Mode,
+    reader: Option<Reader>,
+    history: Vec<Reader>,
+    log: Option<History>,
+    quit: bool,
+}
+
+impl Browser {
+    pub fn new(collection: Collection, jobs: usize) -> Self {
+        let mut entries = collection
+            .newest(1)
+            .with_threads(jobs)
+            .map(|doc| {
+                let line = format!("{} {}", doc.id(), doc.title().unwrap_or_default());
+                Entry { doc, line }
+            })
+            .collect::<Vec<Entry>>();
+        entries.sort_by(|a, b| a.doc.id().cmp(b.doc.id()));
+        let ids = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.doc.id().to_owned(), i))
+            .collect();
+        let mut browser = Browser {
+            entries,
+            ids,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: ListState::default(),
+            mode: Mode::Index,
+            reader: None,
+            history: Vec::new(),
+            log: None,
+            quit: false,
+        };
+        browser.filter();
+        browser
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            if let Err(e) = terminal.draw(|frame| self.draw(frame)) {
+                return Err(Error::TerminalError(e));
+            }
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => self.handle_key(key),
+                Ok(_) => {}
+                Err(e) => return Err(Error::TerminalError(e)),
+            }
+        }
+        Ok(())
+    }
+
+    fn filter(&mut self) {
+        let mut scored = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| fuzzy_score(&self.query, &entry.line).map(|score| (score, i)))
+            .collect::<Vec<(i64, usize)>>();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        match self.mode {
+            Mode::Index => self.handle_index_key(key),
+            Mode::Reader => self.handle_reader_key(key),
+            Mode::References => self.handle_references_key(key),
+        }
+    }
+
+    fn handle_index_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Up => self.selected.select_previous(),
+            KeyCode::Down => self.selected.select_next(),
+            KeyCode::Enter => {
+                if let Some(&entry) = self.selected.selected().and_then(|i| self.matches.get(i)) {
+                    self.history.clear();
+                    self.open(entry);
+                }
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.filter();
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.filter();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_reader_key(&mut self, key: KeyEvent) {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.reader = None;
+                self.history.clear();
+                self.mode = Mode::Index;
+            }
+            KeyCode::Up | KeyCode::Char('k') => reader.scroll = reader.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => reader.scroll = reader.scroll.saturating_add(1),
+            KeyCode::PageUp => reader.scroll = reader.scroll.saturating_sub(PAGE),
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                reader.scroll = reader.scroll.saturating_add(PAGE)
+            }
+            KeyCode::Char('r') | KeyCode::Tab if !reader.references.is_empty() => {
+                reader.selected.select(Some(0));
+                self.mode = Mode::References;
+            }
+            KeyCode::Char('b') | KeyCode::Backspace => match self.history.pop() {
+                Some(previous) => self.reader = Some(previous),
+                None => {
+                    self.reader = None;
+                    self.mode = Mode::Index;
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn handle_references_key(&mut self, key: KeyEvent) {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return,
+        };
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Reader,
+            KeyCode::Up | KeyCode::Char('k') => reader.selected.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => reader.selected.select_next(),
+            KeyCode::Enter => {
+                if let Some(&entry) = reader
+                    .selected
+                    .selected()
+                    .and_then(|i| reader.references.get(i))
+                {
+                    if let Some(current) = self.reader.take() {
+                        self.history.push(current);
+                    }
+                    self.open(entry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open(&mut self, entry: usize) {
+        let doc = &self.entries[entry].doc;
+        if let Some(log) = &self.log {
+            log.record(doc.id()).ok();
+        }
+        let body = match doc.body() {
+            Ok(body) => body.replace('\u{c}', ""),
+            Err(e) => format!("Failed to read document body: {}", e),
+        };
+        let references = doc
+            .references()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| self.ids.get(id).copied())
+            .collect();
+        self.reader = Some(Reader {
+            entry,
+            body,
+            scroll: 0,
+            references,
+            selected: ListState::default(),
+        });
+        self.mode = Mode::Reader;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        match self.mode {
+            Mode::Index => self.draw_index(frame),
+            Mode::Reader => self.draw_reader(frame),
+            Mode::References => {
+                self.draw_reader(frame);
+                self.draw_references(frame);
+            }
+        }
+    }
+
+    fn draw_index(&mut self, frame: &mut Frame) {
+        let [input, panes] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(frame.area());
+        let [list, preview] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(panes);
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.query)).block(
+                Block::default().borders(Borders::ALL).title(format!(
+                    " {}/{} ",
+                    self.matches.len(),
+                    self.entries.len()
+                )),
+            ),
+            input,
+        );
+        let entries = &self.entries;
+        let items = self
+            .matches
+            .iter()
+            .map(|&i| ListItem::new(entries[i].line.as_str()))
+            .collect::<Vec<ListItem>>();
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(INDEX_HELP))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            list,
+            &mut self.selected,
+        );
+        let summary = match self.selected.selected().and_then(|i| self.matches.get(i)) {
+            Some(&i) => fmt_preview(&self.entries[i].doc),
+            None => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(summary)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" summary ")),
+            preview,
+        );
+    }
+
+    fn draw_reader(&mut self, frame: &mut Frame) {
+        let reader = match &self.reader {
+            Some(reader) => reader,
+            None => return,
+        };
+        let doc = &self.entries[reader.entry].doc;
+        frame.render_widget(
+            Paragraph::new(reader.body.as_str())
+                .scroll((reader.scroll, 0))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!(" {} ", doc.id()))
+                        .title_bottom(READER_HELP),
+                ),
+            frame.area(),
+        );
+    }
+
+    fn draw_references(&mut self, frame: &mut Frame) {
+        let entries = &self.entries;
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return,
+        };
+        let area = centered(frame.area(), 60, 60);
+        let items = reader
+            .references
+            .iter()
+            .map(|&i| ListItem::new(entries[i].line.as_str()))
+            .collect::<Vec<ListItem>>();
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" references ")
+                        .title_bottom(REFERENCES_HELP),
+                )
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::REVERSED),
+                ),
+            area,
+            &mut reader.selected,
+        );
+    }
+}
+
+fn fmt_preview(doc: &Document) -> String {
+    match doc.meta() {
+        Ok(meta) => format!("{}\n\n{}", doc.path().display(), meta.fmt_summary()),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Percentage((100 - height) / 2),
+        Constraint::Percentage(height),
+        Constraint::Percentage((100 - height) / 2),
+    ])
+    .areas(area);
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Percentage((100 - width) / 2),
+        Constraint::Percentage(width),
+        Constraint::Percentage((100 - width) / 2),
+    ])
+    .areas(area);
+    area
+}
+
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+    let mut chars = candidate.char_indices();
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_ascii_lowercase();
+        let (i, _) = chars.find(|(_, c)| c.to_ascii_lowercase() == q)?;
+        score += match last {
+            Some(last) if i == last + 1 => 8,
+            Some(last) => -((i - last) as i64).min(8),
+            None => -(i as i64).min(8),
+        };
+        last = Some(i);
+    }
+    Some(score)
+}
+
+pub fn run(collection: Collection, jobs: usize, log: Option<History>) -> Result<()> {
+    let mut browser = Browser::new(collection, jobs);
+    browser.log = log;
+    let mut terminal = match ratatui::try_init() {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            ratatui::restore();
+            return Err(Error::TerminalError(e));
+        }
+    };
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use crate::test::resource_path;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn selected_id(browser: &Browser) -> &str {
+        let i = browser.matches[browser.selected.selected().unwrap()];
+        browser.entries[i].doc.id()
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("", "rfc6468").is_some());
+        assert!(fuzzy_score("sieve", "rfc6468 Sieve Notification").is_some());
+        assert!(fuzzy_score("bgp", "rfc6468 Sieve Notification").is_none());
+        assert!(fuzzy_score("rfc8", "rfc8212 Default") > fuzzy_score("rfc8", "rfc6468 Sieve 8"));
+    }
+
+    #[test]
+    fn test_browse_navigation() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let mut browser = Browser::new(collection, 2);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        for c in "bgp".chars() {
+            browser.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(selected_id(&browser), "rfc8212");
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        browser.handle_key(key(KeyCode::Enter));
+        assert!(matches!(browser.mode, Mode::Reader));
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        browser.handle_key(key(KeyCode::Char('r')));
+        assert!(matches!(browser.mode, Mode::References));
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        browser.handle_key(key(KeyCode::Enter));
+        let reader = browser.reader.as_ref().unwrap();
+        assert_eq!(browser.entries[reader.entry].doc.id(), "rfc8174");
+        assert_eq!(browser.history.len(), 1);
+        browser.handle_key(key(KeyCode::Char('b')));
+        let reader = browser.reader.as_ref().unwrap();
+        assert_eq!(browser.entries[reader.entry].doc.id(), "rfc8212");
+        browser.handle_key(key(KeyCode::Esc));
+        assert!(matches!(browser.mode, Mode::Index));
+        browser.handle_key(key(KeyCode::Esc));
+        assert!(browser.quit);
+        Ok(())
+    }
+}
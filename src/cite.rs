@@ -0,0 +1,495 @@
+use serde_json::{json, Value};
+
+use crate::author::Author;
+use crate::document::{fmt_id, Date, Document};
+use crate::errors::{Error, Result};
+
+pub const STYLES: &[&str] = &["bibtex", "ris", "csl-json", "xml2rfc", "kramdown"];
+
+enum Kind {
+    Rfc(String),
+    Draft(String),
+}
+
+struct Entry {
+    anchor: String,
+    title: String,
+    authors: Vec<Author>,
+    date: Option<Date>,
+    kind: Kind,
+    series: Vec<String>,
+    doi: Option<String>,
+    pages: Option<String>,
+    abstract_: Option<String>,
+    url: String,
+}
+
+impl Entry {
+    fn from(doc: &Document) -> Result<Self> {
+        let meta = doc.meta()?;
+        let first = |key: &str| meta.values(key).first().map(|value| value.to_string());
+        let kind = match doc.id().strip_prefix("rfc") {
+            Some(num) => Kind::Rfc(num.to_string()),
+            None => Kind::Draft(format!("{}-{:02}", doc.id(), -doc.version())),
+        };
+        let (anchor, url, doi) = match &kind {
+            Kind::Rfc(num) => (
+                format!("RFC{}", num),
+                format!("https://www.rfc-editor.org/info/rfc{}", num),
+                first("Identifier.DOI").or_else(|| {
+                    num.parse::<u32>()
+                        .ok()
+                        .map(|num| format!("10.17487/RFC{:04}", num))
+                }),
+            ),
+            Kind::Draft(draft) => (
+                format!(
+                    "I-D.{}",
+                    doc.id().strip_prefix("draft-").unwrap_or(doc.id())
+                ),
+                format!("https://datatracker.ietf.org/doc/html/{}", draft),
+                None,
+            ),
+        };
+        Ok(Entry {
+            anchor,
+            title: doc.title()?,
+            authors: meta
+                .values("Creator")
+                .iter()
+                .map(|creator| Author::parse(creator))
+                .collect(),
+            date: first("Date.Issued").and_then(|date| Date::parse(&date)),
+            kind,
+            series: meta
+                .values("Relation.IsPartOf")
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            doi,
+            pages: first("Format.Extent"),
+            abstract_: first("Description.Abstract"),
+            url,
+        })
+    }
+
+    fn bibtex(&self) -> String {
+        let mut fields = Vec::new();
+        let (kind, key) = match &self.kind {
+            Kind::Rfc(num) => {
+                fields.push(("series", "{Request for Comments}".to_string()));
+                fields.push(("number", num.to_string()));
+                fields.push(("howpublished", format!("{{RFC {}}}", num)));
+                fields.push(("publisher", "{RFC Editor}".to_string()));
+                ("misc", format!("rfc{}", num))
+            }
+            Kind::Draft(draft) => {
+                fields.push(("number", format!("{{{}}}", draft)));
+                fields.push(("type", "{Internet-Draft}".to_string()));
+                fields.push((
+                    "institution",
+                    "{Internet Engineering Task Force}".to_string(),
+                ));
+                fields.push(("note", "{Work in Progress}".to_string()));
+                (
+                    "techreport",
+                    draft.strip_prefix("draft-").unwrap_or(draft).to_string(),
+                )
+            }
+        };
+        if let Some(doi) = &self.doi {
+            fields.push(("doi", format!("{{{}}}", doi)));
+        }
+        fields.push(("url", format!("{{{}}}", self.url)));
+        fields.push((
+            "author",
+            format!(
+                "{{{}}}",
+                self.authors
+                    .iter()
+                    .map(|author| escape_tex(&author.fullname))
+                    .collect::<Vec<String>>()
+                    .join(" and ")
+            ),
+        ));
+        fields.push(("title", format!("{{{{{}}}}}", escape_tex(&self.title))));
+        if let Some(pages) = &self.pages {
+            fields.push(("pagetotal", pages.to_string()));
+        }
+        if let Some(date) = &self.date {
+            fields.push(("year", date.year.to_string()));
+            if let Some(month) = date.month_name() {
+                fields.push(("month", month[..3].to_lowercase()));
+            }
+            if let Some(day) = date.day {
+                fields.push(("day", day.to_string()));
+            }
+        }
+        if let Some(abstract_) = &self.abstract_ {
+            fields.push((
+                "abstract",
+                format!("{{{}}}", escape_tex(&squash(abstract_))),
+            ));
+        }
+        let mut output = format!("@{}{{{},\n", kind, key);
+        for (name, value) in fields {
+            output.push_str(&format!("    {:<16}{},\n", format!("{} =", name), value));
+        }
+        output.push('}');
+        output
+    }
+
+    fn ris(&self) -> String {
+        let mut lines = vec![("TY", "RPRT".to_string()), ("TI", self.title.to_string())];
+        for author in &self.authors {
+            let name = if author.given.is_empty() {
+                author.family.to_string()
+            } else {
+                format!("{}, {}", author.family, author.given)
+            };
+            lines.push(("AU", name));
+        }
+        if let Some(date) = &self.date {
+            lines.push(("PY", date.year.to_string()));
+            lines.push((
+                "DA",
+                format!(
+                    "{}/{}/{}/",
+                    date.year,
+                    date.month.map_or_else(String::new, |m| format!("{:02}", m)),
+                    date.day.map_or_else(String::new, |d| format!("{:02}", d)),
+                ),
+            ));
+        }
+        match &self.kind {
+            Kind::Rfc(num) => {
+                lines.push(("T3", "Request for Comments".to_string()));
+                lines.push(("SN", num.to_string()));
+                lines.push(("PB", "RFC Editor".to_string()));
+            }
+            Kind::Draft(draft) => {
+                lines.push(("M3", "Internet-Draft".to_string()));
+                lines.push(("SN", draft.to_string()));
+                lines.push(("PB", "Internet Engineering Task Force".to_string()));
+            }
+        }
+        if let Some(pages) = &self.pages {
+            lines.push(("SP", pages.to_string()));
+        }
+        if let Some(doi) = &self.doi {
+            lines.push(("DO", doi.to_string()));
+        }
+        lines.push(("UR", self.url.to_string()));
+        if let Some(abstract_) = &self.abstract_ {
+            lines.push(("AB", squash(abstract_)));
+        }
+        lines.push(("ER", String::new()));
+        lines
+            .iter()
+            .map(|(tag, value)| format!("{}  - {}", tag, value).trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn csl_json(&self) -> Value {
+        let mut item = json!({
+            "id": self.anchor,
+            "type": "report",
+            "title": self.title,
+            "author": self
+                .authors
+                .iter()
+                .map(|author| json!({ "family": author.family, "given": author.given }))
+                .collect::<Vec<Value>>(),
+            "URL": self.url,
+        });
+        match &self.kind {
+            Kind::Rfc(num) => {
+                item["genre"] = json!("RFC");
+                item["collection-title"] = json!("Request for Comments");
+                item["number"] = json!(num);
+                item["publisher"] = json!("RFC Editor");
+            }
+            Kind::Draft(draft) => {
+                item["genre"] = json!("Internet-Draft");
+                item["number"] = json!(draft);
+                item["publisher"] = json!("Internet Engineering Task Force");
+                item["note"] = json!("Work in Progress");
+            }
+        }
+        if let Some(date) = &self.date {
+            let mut parts = vec![date.year as usize];
+            if let Some(month) = date.month {
+                parts.push(month);
+                if let Some(day) = date.day {
+                    parts.push(day as usize);
+                }
+            }
+            item["issued"] = json!({ "date-parts": [parts] });
+        }
+        if let Some(doi) = &self.doi {
+            item["DOI"] = json!(doi);
+        }
+        if let Some(pages) = &self.pages {
+            item["number-of-pages"] = json!(pages);
+        }
+        if let Some(abstract_) = &self.abstract_ {
+            item["abstract"] = json!(squash(abstract_));
+        }
+        item
+    }
+
+    fn xml2rfc(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "<reference anchor=\"{}\" target=\"{}\">",
+                escape_xml(&self.anchor),
+                escape_xml(&self.url)
+            ),
+            "  <front>".to_string(),
+            format!("    <title>{}</title>", escape_xml(&self.title)),
+        ];
+        for author in &self.authors {
+            lines.push(format!(
+                "    <author fullname=\"{}\" initials=\"{}\" surname=\"{}\"/>",
+                escape_xml(&author.fullname),
+                escape_xml(&author.initials()),
+                escape_xml(&author.family)
+            ));
+        }
+        if let Some(date) = &self.date {
+            let mut attrs = Vec::new();
+            if let Some(day) = date.day.filter(|_| matches!(self.kind, Kind::Draft(_))) {
+                attrs.push(format!("day=\"{}\"", day));
+            }
+            if let Some(month) = date.month_name() {
+                attrs.push(format!("month=\"{}\"", month));
+            }
+            attrs.push(format!("year=\"{}\"", date.year));
+            lines.push(format!("    <date {}/>", attrs.join(" ")));
+        }
+        if let Some(abstract_) = &self.abstract_ {
+            lines.push("    <abstract>".to_string());
+            for paragraph in abstract_.split("\n\n") {
+                lines.push(format!("      <t>{}</t>", escape_xml(&squash(paragraph))));
+            }
+            lines.push("    </abstract>".to_string());
+        }
+        lines.push("  </front>".to_string());
+        for series in &self.series {
+            let name = fmt_id(series);
+            if let Some((name, value)) = name.split_once(' ') {
+                lines.push(format!(
+                    "  <seriesInfo name=\"{}\" value=\"{}\"/>",
+                    name, value
+                ));
+            }
+        }
+        match &self.kind {
+            Kind::Rfc(num) => lines.push(format!("  <seriesInfo name=\"RFC\" value=\"{}\"/>", num)),
+            Kind::Draft(draft) => lines.push(format!(
+                "  <seriesInfo name=\"Internet-Draft\" value=\"{}\"/>",
+                draft
+            )),
+        }
+        if let Some(doi) = &self.doi {
+            lines.push(format!(
+                "  <seriesInfo name=\"DOI\" value=\"{}\"/>",
+                escape_xml(doi)
+            ));
+        }
+        lines.push("</reference>".to_string());
+        lines.join("\n")
+    }
+
+    fn kramdown(&self) -> String {
+        let mut lines = vec![
+            format!("  {}:", self.anchor),
+            format!("    target: {}", self.url),
+            format!("    title: {}", yaml_scalar(&self.title)),
+            "    author:".to_string(),
+        ];
+        for author in &self.authors {
+            lines.push(format!(
+                "    - ins: {}",
+                yaml_scalar(&author.initials_surname())
+            ));
+            lines.push(format!("      name: {}", yaml_scalar(&author.fullname)));
+        }
+        if let Some(date) = &self.date {
+            let date = match (date.month, date.day) {
+                (Some(month), Some(day)) => format!("{}-{:02}-{:02}", date.year, month, day),
+                (Some(month), None) => format!("{}-{:02}", date.year, month),
+                _ => date.year.to_string(),
+            };
+            lines.push(format!("    date: {}", date));
+        }
+        lines.push("    seriesinfo:".to_string());
+        for series in &self.series {
+            let name = fmt_id(series);
+            if let Some((name, value)) = name.split_once(' ') {
+                lines.push(format!("      {}: '{}'", name, value));
+            }
+        }
+        match &self.kind {
+            Kind::Rfc(num) => lines.push(format!("      RFC: '{}'", num)),
+            Kind::Draft(draft) => lines.push(format!("      Internet-Draft: {}", draft)),
+        }
+        if let Some(doi) = &self.doi {
+            lines.push(format!("      DOI: {}", doi));
+        }
+        lines.join("\n")
+    }
+}
+
+pub fn fmt_entry(doc: &Document, style: &str) -> Result<String> {
+    let entry = Entry::from(doc)?;
+    match style {
+        "bibtex" => Ok(entry.bibtex()),
+        "ris" => Ok(entry.ris()),
+        "csl-json" => Ok(serde_json::to_string_pretty(&entry.csl_json()).unwrap()),
+        "xml2rfc" => Ok(entry.xml2rfc()),
+        "kramdown" => Ok(entry.kramdown()),
+        _ => Err(Error::ImplementationNotFound(format!(
+            "Failed to find an implementation for citation style '{}'",
+            style
+        ))),
+    }
+}
+
+pub fn fmt_bibliography(entries: Vec<String>, style: &str) -> String {
+    match style {
+        "csl-json" => format!("[\n{}\n]", entries.join(",\n")),
+        "kramdown" => entries.join("\n"),
+        _ => entries.join("\n\n"),
+    }
+}
+
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn escape_tex(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => output.push_str("\\textbackslash{}"),
+            '~' => output.push_str("\\textasciitilde{}"),
+            '^' => output.push_str("\\textasciicircum{}"),
+            c if "&%$#_{}".contains(c) => {
+                output.push('\\');
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn yaml_scalar(text: &str) -> String {
+    let special = text.starts_with(|c: char| "!&*-?[]{}|>@`'\"%,#".contains(c))
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.ends_with(':');
+    if special {
+        format!("'{}'", text.replace('\'', "''"))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::resource_path;
+
+    fn entry(file: &str, style: &str) -> Result<String> {
+        let doc = Document::from_path(resource_path(file)).unwrap()?;
+        fmt_entry(&doc, style)
+    }
+
+    #[test]
+    fn test_xml2rfc() -> Result<()> {
+        let output = entry("rfc6468.html", "xml2rfc")?;
+        for string in &[
+            "<reference anchor=\"RFC6468\" target=\"https://www.rfc-editor.org/info/rfc6468\">",
+            "<title>Sieve Notification Mechanism: SIP MESSAGE</title>",
+            "<author fullname=\"Alexey Melnikov\" initials=\"A.\" surname=\"Melnikov\"/>",
+            "<date month=\"February\" year=\"2012\"/>",
+            "<seriesInfo name=\"RFC\" value=\"6468\"/>",
+            "<seriesInfo name=\"DOI\" value=\"10.17487/RFC6468\"/>",
+        ] {
+            assert!(output.contains(string), "'{}' not found in output", string);
+        }
+        let output = entry("draft-ietf-sidrops-rpkimaxlen-05.html", "xml2rfc")?;
+        for string in &[
+            "<reference anchor=\"I-D.ietf-sidrops-rpkimaxlen\" \
+             target=\"https://datatracker.ietf.org/doc/html/draft-ietf-sidrops-rpkimaxlen-05\">",
+            "<author fullname=\"Job Snijders\" initials=\"J.\" surname=\"Snijders\"/>",
+            "<date day=\"2\" month=\"November\" year=\"2020\"/>",
+            "<seriesInfo name=\"Internet-Draft\" value=\"draft-ietf-sidrops-rpkimaxlen-05\"/>",
+        ] {
+            assert!(output.contains(string), "'{}' not found in output", string);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bibtex() -> Result<()> {
+        let output = entry("rfc8174.xml", "bibtex")?;
+        for string in &[
+            "@misc{rfc8174,\n",
+            "    number =        8174,\n",
+            "    author =        {Barry Leiba},\n",
+            "    title =         {{Ambiguity of Uppercase vs Lowercase in RFC 2119 Key Words}},\n",
+            "    month =         may,\n",
+        ] {
+            assert!(output.contains(string), "'{}' not found in output", string);
+        }
+        let output = entry("draft-ietf-sidrops-rpkimaxlen-05.html", "bibtex")?;
+        assert!(output.starts_with("@techreport{ietf-sidrops-rpkimaxlen-05,\n"));
+        assert_eq!(
+            escape_tex(r"50% of C:\ ~ x^2_{i}"),
+            r"50\% of C:\textbackslash{} \textasciitilde{} x\textasciicircum{}2\_\{i\}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ris_and_kramdown() -> Result<()> {
+        let output = entry("rfc8212.xml", "ris")?;
+        assert!(output.starts_with("TY  - RPRT\n"));
+        assert!(output.contains("AU  - Snijders, Job\n"));
+        assert!(output.ends_with("\nER  -"));
+        let output = entry("rfc8212.xml", "kramdown")?;
+        assert!(output.starts_with("  RFC8212:\n"));
+        assert!(output.contains("    - ins: J. Snijders\n      name: Job Snijders\n"));
+        assert!(output.contains("      RFC: '8212'\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_csl_json() -> Result<()> {
+        let entries = vec![
+            entry("rfc8174.xml", "csl-json")?,
+            entry("rfc8212.xml", "csl-json")?,
+        ];
+        let items: Value = serde_json::from_str(&fmt_bibliography(entries, "csl-json")).unwrap();
+        assert_eq!(items[0]["id"], "RFC8174");
+        assert_eq!(items[0]["issued"]["date-parts"], json!([[2017, 5]]));
+        assert_eq!(items[1]["author"][2]["family"], "Hankins");
+        assert!(matches!(
+            entry("rfc8212.xml", "mla"),
+            Err(Error::ImplementationNotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -2,29 +2,45 @@ use std::convert::TryInto;
 use std::ffi::{OsStr, OsString};
 use std::io::stdout;
 use std::path::PathBuf;
+use std::process;
 use std::result;
 use std::str::FromStr;
 
 use clap::{crate_authors, crate_description, crate_name, crate_version};
 use directories::ProjectDirs;
 
+use crate::annotate::ACTIONS;
+use crate::cite::STYLES;
 use crate::cmd::{ArgProvider, CmdExec};
-use crate::errors::{Error, Result};
+use crate::collection::TYPES;
+use crate::document::{Policy, POLICIES};
+use crate::errata::STATUSES;
+use crate::errors::{Error, Result, EXIT_USAGE};
+use crate::extract::KINDS;
+use crate::profile::PROFILES;
+use crate::requirements::OUTPUTS;
+
+const DEFAULT_REMOTE: &str = "rsync.tools.ietf.org::tools.html";
 
 pub trait DefaultsProvider {
     fn dir(&self) -> &OsStr;
     fn jobs(&self) -> &str;
+    fn profiles(&self) -> &OsStr;
 }
 
 pub struct Defaults {
     dir: OsString,
     jobs: String,
+    profiles: OsString,
 }
 
 impl Defaults {
     pub fn get() -> Result<Self> {
-        let dir = match ProjectDirs::from("", "", "rfz") {
-            Some(dirs) => dirs.data_dir().as_os_str().to_owned(),
+        let (dir, profiles) = match ProjectDirs::from("", "", "rfz") {
+            Some(dirs) => (
+                dirs.data_dir().as_os_str().to_owned(),
+                dirs.config_dir().join(PROFILES).into_os_string(),
+            ),
             None => {
                 return Err(Error::UserDirectories(
                     "Failed to infer user directory locations".to_string(),
@@ -32,7 +48,11 @@ impl Defaults {
             }
         };
         let jobs = num_cpus::get().to_string();
-        Ok(Defaults { dir, jobs })
+        Ok(Defaults {
+            dir,
+            jobs,
+            profiles,
+        })
     }
 }
 
@@ -44,6 +64,10 @@ impl DefaultsProvider for Defaults {
     fn jobs(&self) -> &str {
         &self.jobs
     }
+
+    fn profiles(&self) -> &OsStr {
+        &self.profiles
+    }
 }
 
 pub struct Cli<'a> {
@@ -55,6 +79,10 @@ impl<'a> Cli<'a> {
     pub fn init(defaults: &'a dyn DefaultsProvider) -> Self {
         match Self::init_from(defaults, None) {
             Ok(cli) => cli,
+            Err(e) if e.use_stderr() => {
+                eprintln!("{}", e.message);
+                process::exit(EXIT_USAGE)
+            }
             Err(e) => e.exit(),
         }
     }
@@ -74,7 +102,7 @@ impl<'a> Cli<'a> {
         })
     }
 
-    fn build_cli(defaults: &'a dyn DefaultsProvider) -> clap::App {
+    fn build_cli(defaults: &'a dyn DefaultsProvider) -> clap::App<'a, 'a> {
         clap::app_from_crate!()
             .setting(clap::AppSettings::SubcommandRequired)
             .arg(
@@ -93,7 +121,7 @@ impl<'a> Cli<'a> {
                     .takes_value(true)
                     .global(true)
                     .default_value_os(defaults.dir())
-                    .help("Directory containing IETF html docs"),
+                    .help("Directory containing IETF html, txt or xml docs"),
             )
             .arg(
                 clap::Arg::with_name("verbosity")
@@ -102,6 +130,80 @@ impl<'a> Cli<'a> {
                     .global(true)
                     .help("Increase output verbosity"),
             )
+            .arg(
+                clap::Arg::with_name("policy")
+                    .long("parse")
+                    .takes_value(true)
+                    .global(true)
+                    .possible_values(POLICIES)
+                    .default_value("strict")
+                    .help(
+                        "How to handle malformed metadata: reject, or keep first/last/all values",
+                    ),
+            )
+            .arg(
+                clap::Arg::with_name("no-history")
+                    .long("no-history")
+                    .global(true)
+                    .help(
+                        "Do not record viewed documents in the reading history \
+                         (create a 'no-history' file in <dir> to never record them)",
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("authors")
+                    .about(
+                        "List authors with document counts, \
+                         or the documents written by <name>",
+                    )
+                    .arg(
+                        clap::Arg::with_name("name")
+                            .help("Author name (e.g. 'Job Snijders' or 'Snijders, Job')"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("browse")
+                    .about("Interactively browse the local document mirror")
+                    .arg(
+                        clap::Arg::with_name("type")
+                            .short("t")
+                            .long("type")
+                            .takes_value(true)
+                            .multiple(true)
+                            .possible_values(TYPES)
+                            .help("Limit documents by type"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("cite")
+                    .about(
+                        "Print bibliography entries for the document(s) identified by <id>, \
+                         or for every document matching --type",
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .multiple(true)
+                            .help("Document or sub-series identifier (e.g. 'rfc6468' or 'bcp14')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("style")
+                            .short("s")
+                            .long("style")
+                            .possible_values(STYLES)
+                            .default_value("bibtex")
+                            .help("Citation style"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("type")
+                            .short("t")
+                            .long("type")
+                            .takes_value(true)
+                            .multiple(true)
+                            .possible_values(TYPES)
+                            .conflicts_with("id")
+                            .help("Limit documents by type"),
+                    ),
+            )
             .subcommand(
                 clap::SubCommand::with_name("completions")
                     .about("Print shell completion script")
@@ -112,6 +214,79 @@ impl<'a> Cli<'a> {
                             .help("Shell for which to generate completion script"),
                     ),
             )
+            .subcommand(
+                clap::SubCommand::with_name("errata")
+                    .about("Print the errata reported against the RFC identified by <id>")
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help("RFC identifier (e.g. 'rfc6468')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("status")
+                            .short("s")
+                            .long("status")
+                            .possible_values(STATUSES)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .help("Only print errata with the given status"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("extract")
+                    .about(
+                        "Extract code, YANG modules, ABNF or ASN.1 modules from the \
+                         document identified by <id>",
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help("Document identifier or path (e.g. 'rfc8212')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("kind")
+                            .short("k")
+                            .long("kind")
+                            .possible_values(KINDS)
+                            .default_value("code")
+                            .help("Kind of artifact to extract"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("output-dir")
+                            .short("o")
+                            .long("output-dir")
+                            .default_value(".")
+                            .help("Directory to write the extracted files to"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("stdout")
+                            .long("stdout")
+                            .help("Print the extracted artifacts instead of writing files"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("iana")
+                    .about(
+                        "List the IANA registries touched by the document identified by <id>, \
+                         or the registries touched by local documents",
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .conflicts_with("registry")
+                            .help("Document identifier or path (e.g. 'rfc6468')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("registry")
+                            .long("registry")
+                            .takes_value(true)
+                            .help("List the documents that touch registries matching <registry>"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("json")
+                            .long("json")
+                            .help("Print registries as JSON"),
+                    ),
+            )
             .subcommand(
                 clap::SubCommand::with_name("index")
                     .about(
@@ -124,8 +299,203 @@ impl<'a> Cli<'a> {
                             .long("type")
                             .takes_value(true)
                             .multiple(true)
-                            .possible_values(&["draft", "rfc", "bcp", "std"])
+                            .possible_values(TYPES)
                             .help("Limit output by document type"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("active-only")
+                            .long("active-only")
+                            .help("Omit expired and replaced drafts"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("tag")
+                            .long("tag")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .help("Limit output to documents with any of the given tags"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("starred")
+                            .long("starred")
+                            .help("Limit output to starred documents"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("note")
+                    .about("Add, remove or list personal notes on documents")
+                    .arg(
+                        clap::Arg::with_name("action")
+                            .required(true)
+                            .possible_values(ACTIONS)
+                            .help("Action to perform"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required_ifs(&[("action", "add"), ("action", "rm")])
+                            .help("Document identifier (e.g. 'rfc6468')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("value")
+                            .multiple(true)
+                            .required_if("action", "add")
+                            .help("Text of the note to add, or the number of the note to remove"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("prune")
+                    .about("Remove old, replaced or expired drafts from the local mirror")
+                    .arg(
+                        clap::Arg::with_name("keep")
+                            .long("keep")
+                            .takes_value(true)
+                            .validator(|value| match u8::from_str(&value) {
+                                Ok(n) if n > 0 => Ok(()),
+                                _ => Err("keep must be a positive number".to_string()),
+                            })
+                            .help("Keep only the latest <keep> revisions of each draft"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("replaced")
+                            .long("replaced")
+                            .help("Remove every revision of drafts that have been published as an RFC"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("expired")
+                            .long("expired")
+                            .takes_value(true)
+                            .validator(|value| match i64::from_str(&value) {
+                                Ok(n) if n >= 0 => Ok(()),
+                                _ => Err("expired must be a number of days".to_string()),
+                            })
+                            .help("Remove every revision of drafts that expired more than <expired> days ago"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("forget")
+                            .long("forget")
+                            .takes_value(true)
+                            .multiple(true)
+                            .value_name("id")
+                            .help("Drop documents or draft revisions from the list of pruned files, so that sync fetches them again"),
+                    )
+                    .group(
+                        clap::ArgGroup::with_name("retention")
+                            .args(&["keep", "replaced", "expired", "forget"])
+                            .multiple(true)
+                            .required(true),
+                    )
+                    .arg(
+                        clap::Arg::with_name("dry-run")
+                            .short("n")
+                            .long("dry-run")
+                            .help("Show what would be removed or forgotten without changing the local mirror"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("recent")
+                    .about("List recently viewed documents, most recent first")
+                    .arg(
+                        clap::Arg::with_name("limit")
+                            .short("n")
+                            .long("limit")
+                            .default_value("10")
+                            .validator(|value| match usize::from_str(&value) {
+                                Ok(n) if n > 0 => Ok(()),
+                                _ => Err("limit must be a positive number".to_string()),
+                            })
+                            .help("Maximum number of documents to list"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("clear")
+                            .long("clear")
+                            .help("Clear the reading history"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("requirements")
+                    .about(
+                        "List the sentences of the document identified by <id> \
+                         that contain BCP 14 (RFC 2119) key words",
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help(
+                                "Document identifier, draft revision or path \
+                                 (e.g. 'rfc6468' or 'draft-ietf-sidrops-rpkimaxlen-04')",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::with_name("output")
+                            .short("o")
+                            .long("output")
+                            .possible_values(OUTPUTS)
+                            .default_value("table")
+                            .help("Output format"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("diff")
+                            .long("diff")
+                            .takes_value(true)
+                            .help("List requirements added or removed in another document or revision"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("section")
+                    .about("Print the text of section <number> of the document identified by <id>")
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help("Document identifier or path (e.g. 'rfc6468')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("section")
+                            .required(true)
+                            .help("Section number or title (e.g. '2.6', 'A.1' or 'abstract')"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("serve")
+                    .about("Serve the local document mirror over HTTP")
+                    .arg(
+                        clap::Arg::with_name("listen")
+                            .short("l")
+                            .long("listen")
+                            .default_value("127.0.0.1:8080")
+                            .help("Address and port to listen on"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("show")
+                    .about("Print a summary of the document(s) identified by <id>")
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help("Document or sub-series identifier (e.g. 'rfc6468' or 'bcp14')"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("star")
+                    .about("Star, unstar or list starred documents")
+                    .arg(
+                        clap::Arg::with_name("action")
+                            .required(true)
+                            .possible_values(ACTIONS)
+                            .help("Action to perform"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required_ifs(&[("action", "add"), ("action", "rm")])
+                            .help("Document identifier (e.g. 'rfc6468')"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("stats")
+                    .about("Print statistics about the local document mirror")
+                    .arg(
+                        clap::Arg::with_name("json")
+                            .long("json")
+                            .help("Print statistics as JSON"),
                     ),
             )
             .subcommand(
@@ -144,14 +514,208 @@ impl<'a> Cli<'a> {
                         clap::Arg::with_name("remote")
                             .short("r")
                             .long("remote")
-                            .default_value("rsync.tools.ietf.org::tools.html")
-                            .help("Remote 'rsync' target to sync from"),
+                            .takes_value(true)
+                            .required_ifs(&[("format", "txt"), ("format", "xml")])
+                            .help(
+                                "Remote 'rsync' target to sync from [default: \
+                                 rsync.tools.ietf.org::tools.html, which only carries html; \
+                                 required with --format txt or xml]",
+                            ),
                     )
                     .arg(
                         clap::Arg::with_name("command")
                             .long("command")
                             .default_value("rsync")
                             .help("Rsync command"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("format")
+                            .short("f")
+                            .long("format")
+                            .takes_value(true)
+                            .multiple(true)
+                            .possible_values(&["html", "txt", "xml"])
+                            .default_value("html")
+                            .help("Document formats to sync"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("profile")
+                            .short("P")
+                            .long("profile")
+                            .takes_value(true)
+                            .help("Sync profile to apply, as defined in the <profiles> file"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("profiles")
+                            .long("profiles")
+                            .takes_value(true)
+                            .default_value_os(defaults.profiles())
+                            .help("File containing sync profile definitions"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("type")
+                            .short("t")
+                            .long("type")
+                            .takes_value(true)
+                            .multiple(true)
+                            .possible_values(TYPES)
+                            .help("Only sync documents of these types"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("group")
+                            .short("g")
+                            .long("group")
+                            .takes_value(true)
+                            .multiple(true)
+                            .help("Only sync the drafts of these working or research groups"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("revisions")
+                            .long("revisions")
+                            .takes_value(true)
+                            .validator(|value| match u8::from_str(&value) {
+                                Ok(n) if n > 0 => Ok(()),
+                                _ => Err("revisions must be a positive number".to_string()),
+                            })
+                            .help("Only sync the latest <revisions> revisions of each draft"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("include")
+                            .long("include")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .help(
+                                "Only sync files matching this 'rsync' pattern (replaces --format)",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::with_name("exclude")
+                            .long("exclude")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .help("Never sync files matching this 'rsync' pattern"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("files-from")
+                            .long("files-from")
+                            .conflicts_with_all(&["profile", "type", "group", "revisions"])
+                            .takes_value(true)
+                            .help(
+                                "Only sync the files listed in <files-from> \
+                                 (e.g. the output of 'rfz verify --list', or '-' for stdin)",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::with_name("dry-run")
+                            .short("n")
+                            .long("dry-run")
+                            .help(
+                                "Show what would be transferred without changing the local mirror",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::with_name("delete")
+                            .long("delete")
+                            .conflicts_with("files-from")
+                            .help(
+                                "Delete local documents that no longer exist on the remote \
+                                 or have left the profile",
+                            ),
+                    )
+                    .arg(
+                        clap::Arg::with_name("timeout")
+                            .long("timeout")
+                            .takes_value(true)
+                            .validator(|value| match u64::from_str(&value) {
+                                Ok(_) => Ok(()),
+                                Err(_) => Err("timeout must be a number of seconds".to_string()),
+                            })
+                            .help("Abort the sync if no data is transferred for <timeout> seconds"),
+                    )
+                    .arg(clap::Arg::with_name("staged").long("staged").help(
+                        "Sync into a new snapshot of the mirror, \
+                                 and only switch to it once the sync has succeeded \
+                                 (otherwise the current snapshot is changed in place; unix only)",
+                    ))
+                    .arg(
+                        clap::Arg::with_name("progress")
+                            .short("p")
+                            .long("progress")
+                            .help("Show a progress bar while syncing"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("rfc-index")
+                            .long("rfc-index")
+                            .help("Also sync the RFC Editor 'rfc-index.xml' metadata overlay"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("rfc-index-remote")
+                            .long("rfc-index-remote")
+                            .default_value("rsync.rfc-editor.org::rfcs/rfc-index.xml")
+                            .help("Remote 'rsync' target for 'rfc-index.xml'"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("errata")
+                            .long("errata")
+                            .help("Also fetch the RFC Editor 'errata.json' errata overlay"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("errata-remote")
+                            .long("errata-remote")
+                            .default_value("https://www.rfc-editor.org/errata.json")
+                            .help("URL to fetch 'errata.json' from, using 'curl'"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("tag")
+                    .about("Add, remove or list personal tags on documents")
+                    .arg(
+                        clap::Arg::with_name("action")
+                            .required(true)
+                            .possible_values(ACTIONS)
+                            .help("Action to perform"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required_ifs(&[("action", "add"), ("action", "rm")])
+                            .help("Document identifier (e.g. 'rfc6468')"),
+                    )
+                    .arg(
+                        clap::Arg::with_name("value")
+                            .multiple(true)
+                            .required_ifs(&[("action", "add"), ("action", "rm")])
+                            .help("Tags to add or remove"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("toc")
+                    .about("Print the table of contents of the document identified by <id>")
+                    .arg(
+                        clap::Arg::with_name("id")
+                            .required(true)
+                            .help("Document identifier or path (e.g. 'rfc6468')"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("verify")
+                    .about("Check the local document mirror for damaged or incomplete files")
+                    .arg(
+                        clap::Arg::with_name("list")
+                            .long("list")
+                            .help("Only print the paths of empty, truncated or unreadable documents, to be re-fetched"),
+                    ),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("wg")
+                    .about(
+                        "List working and research groups with active draft counts, \
+                         or the drafts and RFCs of group <name>",
+                    )
+                    .arg(
+                        clap::Arg::with_name("name")
+                            .help("Working or research group acronym (e.g. 'sidrops')"),
                     ),
             )
     }
@@ -207,31 +771,241 @@ impl ArgProvider for CliArgs<'_> {
         }
     }
 
+    fn policy(&self) -> Policy {
+        Policy::from_str(self.0.value_of("policy").unwrap()).unwrap()
+    }
+
     fn path(&self) -> PathBuf {
         PathBuf::from(self.0.value_of("doc").unwrap())
     }
 
+    fn doc_id(&self) -> &str {
+        self.0.value_of("id").unwrap()
+    }
+
+    fn doc_ids(&self) -> Vec<&str> {
+        match self.0.values_of("id") {
+            Some(values) => values.collect(),
+            None => Vec::new(),
+        }
+    }
+
     fn rsync_cmd(&self) -> &str {
         self.0.value_of("command").unwrap()
     }
 
     fn rsync_remote(&self) -> &str {
-        self.0.value_of("remote").unwrap()
+        self.0.value_of("remote").unwrap_or(DEFAULT_REMOTE)
     }
 
-    fn types(&self) -> Option<Vec<&str>> {
-        match self.0.values_of("type") {
-            Some(values) => Some(values.collect()),
-            None => None,
+    fn formats(&self) -> Vec<&str> {
+        self.0.values_of("format").unwrap().collect()
+    }
+
+    fn rfc_index_remote(&self) -> Option<&str> {
+        if self.0.is_present("rfc-index") {
+            self.0.value_of("rfc-index-remote")
+        } else {
+            None
         }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    fn errata_remote(&self) -> Option<&str> {
+        if self.0.is_present("errata") {
+            self.0.value_of("errata-remote")
+        } else {
+            None
+        }
+    }
 
-    use crate::test::resource_path;
+    fn types(&self) -> Option<Vec<&str>> {
+        self.0.values_of("type").map(|values| values.collect())
+    }
+
+    fn active_only(&self) -> bool {
+        self.0.is_present("active-only")
+    }
+
+    fn listen(&self) -> &str {
+        self.0.value_of("listen").unwrap()
+    }
+
+    fn style(&self) -> &str {
+        self.0.value_of("style").unwrap()
+    }
+
+    fn author(&self) -> Option<&str> {
+        self.0.value_of("name")
+    }
+
+    fn group(&self) -> Option<&str> {
+        self.0.value_of("name")
+    }
+
+    fn json(&self) -> bool {
+        self.0.is_present("json")
+    }
+
+    fn list(&self) -> bool {
+        self.0.is_present("list")
+    }
+
+    fn files_from(&self) -> Option<&str> {
+        self.0.value_of("files-from")
+    }
+
+    fn profile(&self) -> Option<&str> {
+        self.0.value_of("profile")
+    }
+
+    fn profiles(&self) -> PathBuf {
+        PathBuf::from(self.0.value_of_os("profiles").unwrap())
+    }
+
+    fn groups(&self) -> Vec<&str> {
+        self.0
+            .values_of("group")
+            .map_or_else(Vec::new, |groups| groups.collect())
+    }
+
+    fn revisions(&self) -> Option<u8> {
+        self.0
+            .value_of("revisions")
+            .map(|value| u8::from_str(value).unwrap())
+    }
+
+    fn includes(&self) -> Vec<&str> {
+        self.0
+            .values_of("include")
+            .map_or_else(Vec::new, |patterns| patterns.collect())
+    }
+
+    fn excludes(&self) -> Vec<&str> {
+        self.0
+            .values_of("exclude")
+            .map_or_else(Vec::new, |patterns| patterns.collect())
+    }
+
+    fn keep(&self) -> Option<u8> {
+        self.0
+            .value_of("keep")
+            .map(|value| u8::from_str(value).unwrap())
+    }
+
+    fn replaced(&self) -> bool {
+        self.0.is_present("replaced")
+    }
+
+    fn expired(&self) -> Option<i64> {
+        self.0
+            .value_of("expired")
+            .map(|value| i64::from_str(value).unwrap())
+    }
+
+    fn forget(&self) -> Vec<&str> {
+        self.0
+            .values_of("forget")
+            .map_or_else(Vec::new, |values| values.collect())
+    }
+
+    fn dry_run(&self) -> bool {
+        self.0.is_present("dry-run")
+    }
+
+    fn delete(&self) -> bool {
+        self.0.is_present("delete")
+    }
+
+    fn timeout(&self) -> Option<u64> {
+        self.0
+            .value_of("timeout")
+            .map(|value| u64::from_str(value).unwrap())
+    }
+
+    fn progress(&self) -> bool {
+        self.0.is_present("progress")
+    }
+
+    fn staged(&self) -> bool {
+        self.0.is_present("staged")
+    }
+
+    fn tags(&self) -> Vec<&str> {
+        self.0
+            .values_of("tag")
+            .map_or_else(Vec::new, |tags| tags.collect())
+    }
+
+    fn starred(&self) -> bool {
+        self.0.is_present("starred")
+    }
+
+    fn action(&self) -> &str {
+        self.0.value_of("action").unwrap()
+    }
+
+    fn target(&self) -> Option<&str> {
+        self.0.value_of("id")
+    }
+
+    fn values(&self) -> Vec<&str> {
+        self.0
+            .values_of("value")
+            .map_or_else(Vec::new, |values| values.collect())
+    }
+
+    fn history(&self) -> bool {
+        !self.0.is_present("no-history")
+    }
+
+    fn limit(&self) -> usize {
+        usize::from_str(self.0.value_of("limit").unwrap()).unwrap()
+    }
+
+    fn clear(&self) -> bool {
+        self.0.is_present("clear")
+    }
+
+    fn section(&self) -> &str {
+        self.0.value_of("section").unwrap()
+    }
+
+    fn output(&self) -> &str {
+        self.0.value_of("output").unwrap()
+    }
+
+    fn diff(&self) -> Option<&str> {
+        self.0.value_of("diff")
+    }
+
+    fn kind(&self) -> &str {
+        self.0.value_of("kind").unwrap()
+    }
+
+    fn output_dir(&self) -> PathBuf {
+        PathBuf::from(self.0.value_of_os("output-dir").unwrap())
+    }
+
+    fn stdout(&self) -> bool {
+        self.0.is_present("stdout")
+    }
+
+    fn registry(&self) -> Option<&str> {
+        self.0.value_of("registry")
+    }
+
+    fn statuses(&self) -> Vec<&str> {
+        self.0
+            .values_of("status")
+            .map_or_else(Vec::new, |statuses| statuses.collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::resource_path;
 
     use std::str::FromStr;
 
@@ -244,6 +1018,9 @@ mod test {
         fn dir(&self) -> &OsStr {
             OsStr::new("/home/foo/rfz")
         }
+        fn profiles(&self) -> &OsStr {
+            OsStr::new("/home/foo/.config/rfz/profiles")
+        }
     }
 
     #[test]
@@ -275,6 +1052,7 @@ mod test {
                 assert_eq!(cli_args.jobs(), 1);
                 assert_eq!(cli_args.dir(), PathBuf::from("/home/foo/rfz"));
                 assert_eq!(cli_args.types(), None);
+                assert_eq!(cli_args.policy(), Policy::Strict);
             }
             _ => panic!("Cli parsing failed"),
         }
@@ -297,6 +1075,126 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_dummy_browse() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "browse", "-t", "rfc", "bcp"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "browse");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.types(), Some(vec!["rfc", "bcp"]));
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_authors() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "authors", "Snijders, Job"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "authors");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.author(), Some("Snijders, Job"));
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_index_lenient() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "-v", "--parse", "last", "index"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "index");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.policy(), Policy::KeepLast);
+                assert_eq!(cli_args.verbosity(), 1);
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_index_active_only() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "index", "--active-only"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "index");
+                assert!(CliArgs::from(args).active_only());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_stats() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "stats", "--json"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "stats");
+                assert!(CliArgs::from(args).json());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_wg() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "wg", "sidrops"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "wg");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.group(), Some("sidrops"));
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_cite() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "cite", "rfc6468", "bcp14", "-s", "ris"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "cite");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.doc_ids(), vec!["rfc6468", "bcp14"]);
+                assert_eq!(cli_args.style(), "ris");
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_serve() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "serve"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "serve");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.listen(), "127.0.0.1:8080");
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
     #[test]
     fn test_dummy_summary() {
         let defaults = DummyDefaults {};
@@ -312,6 +1210,21 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_dummy_show() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "show", "bcp14"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "show");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.doc_id(), "bcp14");
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
     #[test]
     fn test_dummy_sync() {
         let defaults = DummyDefaults {};
@@ -323,7 +1236,222 @@ mod test {
                 let cli_args = CliArgs::from(args);
                 assert_eq!(cli_args.rsync_cmd(), "rsync");
                 assert_eq!(cli_args.rsync_remote(), "rsync.tools.ietf.org::tools.html");
-                assert_eq!(cli_args.verbosity(), 1)
+                assert_eq!(cli_args.formats(), vec!["html"]);
+                assert_eq!(cli_args.rfc_index_remote(), None);
+                assert_eq!(cli_args.errata_remote(), None);
+                assert_eq!(cli_args.verbosity(), 1);
+                assert!(!cli_args.dry_run());
+                assert_eq!(cli_args.timeout(), None)
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_sync_options() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec![
+            "rfz",
+            "sync",
+            "--dry-run",
+            "--delete",
+            "--timeout",
+            "60",
+            "--progress",
+            "--staged",
+        ]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "sync");
+                let cli_args = CliArgs::from(args);
+                assert!(cli_args.dry_run());
+                assert!(cli_args.delete());
+                assert_eq!(cli_args.timeout(), Some(60));
+                assert!(cli_args.progress());
+                assert!(cli_args.staged());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_sync_profile() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec![
+            "rfz",
+            "sync",
+            "--profile",
+            "laptop",
+            "--type",
+            "rfc",
+            "draft",
+            "--group",
+            "sidrops",
+            "grow",
+            "--revisions",
+            "2",
+            "--exclude",
+            "*-00.html",
+        ]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "sync");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.profile(), Some("laptop"));
+                assert_eq!(
+                    cli_args.profiles(),
+                    PathBuf::from("/home/foo/.config/rfz/profiles")
+                );
+                assert_eq!(cli_args.types(), Some(vec!["rfc", "draft"]));
+                assert_eq!(cli_args.groups(), vec!["sidrops", "grow"]);
+                assert_eq!(cli_args.revisions(), Some(2));
+                assert!(cli_args.includes().is_empty());
+                assert_eq!(cli_args.excludes(), vec!["*-00.html"]);
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+        let argv = Some(vec!["rfz", "sync", "--revisions", "1", "--files-from", "-"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+    }
+
+    #[test]
+    fn test_dummy_prune() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "prune", "--keep", "2", "--expired", "90", "-n"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "prune");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.keep(), Some(2));
+                assert!(!cli_args.replaced());
+                assert_eq!(cli_args.expired(), Some(90));
+                assert!(cli_args.dry_run());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+        let argv = Some(vec!["rfz", "prune", "--dry-run"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+        let argv = Some(vec!["rfz", "prune", "--forget", "rfc6468", "rfc8212"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (_, Some(args)) => {
+                assert_eq!(CliArgs::from(args).forget(), vec!["rfc6468", "rfc8212"]);
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+    }
+
+    #[test]
+    fn test_dummy_annotations() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "tag", "add", "rfc6468", "sieve", "sip"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "tag");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.action(), "add");
+                assert_eq!(cli_args.target(), Some("rfc6468"));
+                assert_eq!(cli_args.values(), vec!["sieve", "sip"]);
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+        let argv = Some(vec![
+            "rfz",
+            "index",
+            "--tag",
+            "sieve",
+            "--tag",
+            "sip",
+            "--starred",
+        ]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (_, Some(args)) => {
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.tags(), vec!["sieve", "sip"]);
+                assert!(cli_args.starred());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+        let argv = Some(vec!["rfz", "star", "list"]);
+        assert!(Cli::init_from(&defaults, argv).is_ok());
+        let argv = Some(vec!["rfz", "tag", "add", "rfc6468"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+        let argv = Some(vec!["rfz", "note", "edit", "rfc6468"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+    }
+
+    #[test]
+    fn test_dummy_recent() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "--no-history", "recent", "-n", "3"]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "recent");
+                let cli_args = CliArgs::from(args);
+                assert!(!cli_args.history());
+                assert_eq!(cli_args.limit(), 3);
+                assert!(!cli_args.clear());
+            }
+            _ => panic!("Cli parsing failed"),
+        }
+        let argv = Some(vec!["rfz", "recent", "--limit", "0"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+    }
+
+    #[test]
+    fn test_dummy_sync_bad_timeout() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec!["rfz", "sync", "--timeout", "soon"]);
+        assert!(Cli::init_from(&defaults, argv).is_err());
+    }
+
+    #[test]
+    fn test_dummy_sync_formats_need_remote() {
+        let defaults = DummyDefaults {};
+        for format in &["txt", "xml"] {
+            let argv = Some(vec!["rfz", "sync", "--format", "html", format]);
+            assert!(Cli::init_from(&defaults, argv).is_err());
+        }
+    }
+
+    #[test]
+    fn test_dummy_sync_formats() {
+        let defaults = DummyDefaults {};
+        let argv = Some(vec![
+            "rfz",
+            "sync",
+            "--remote",
+            "rsync.rfc-editor.org::rfcs",
+            "--rfc-index",
+            "--errata",
+            "--files-from",
+            "-",
+            "--format",
+            "txt",
+            "xml",
+        ]);
+        let cli = Cli::init_from(&defaults, argv).unwrap();
+        match cli.args.subcommand() {
+            (subcommand, Some(args)) => {
+                assert_eq!(subcommand, "sync");
+                let cli_args = CliArgs::from(args);
+                assert_eq!(cli_args.rsync_remote(), "rsync.rfc-editor.org::rfcs");
+                assert_eq!(cli_args.formats(), vec!["txt", "xml"]);
+                assert_eq!(cli_args.files_from(), Some("-"));
+                assert_eq!(
+                    cli_args.rfc_index_remote(),
+                    Some("rsync.rfc-editor.org::rfcs/rfc-index.xml")
+                );
+                assert_eq!(
+                    cli_args.errata_remote(),
+                    Some("https://www.rfc-editor.org/errata.json")
+                );
             }
             _ => panic!("Cli parsing failed"),
         }
@@ -333,7 +1461,14 @@ mod test {
     fn test_exec_index() -> Result<()> {
         let defaults = Defaults::get()?;
         let dir = resource_path("");
-        let argv = Some(vec!["rfz", "index", "-d", dir.to_str().unwrap()]);
+        let argv = Some(vec![
+            "rfz",
+            "--parse",
+            "first",
+            "index",
+            "-d",
+            dir.to_str().unwrap(),
+        ]);
         let cli = Cli::init_from(&defaults, argv).unwrap();
         cli.run()
     }
@@ -1,21 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
 use std::io::{stdout, Write};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
 
 use pipeliner::Pipeline;
 
+use crate::annotate::Annotations;
+use crate::author::AuthorIndex;
+use crate::browse;
+use crate::cite;
 use crate::collection::Collection;
-use crate::document::Document;
+use crate::document::{fmt_id, normalize_id, split_revision, Document, Policy};
+use crate::errata::{self, Errata, Erratum, ERRATA};
 use crate::errors::{Error, Result};
+use crate::extract;
+use crate::history::History;
+use crate::iana::{Considerations, IanaIndex, IANA_CACHE};
+use crate::mirror::{is_document, snapshot, Lock, Pin, Stage, SYNC_LIST};
+use crate::overlay::{Overlay, RFC_INDEX};
+use crate::profile::{parse_listing, Profile};
+use crate::prune::{self, Retention, PRUNED};
+use crate::requirements::{self, Row};
+use crate::section::Section;
+use crate::serve;
+use crate::stats::Stats;
+use crate::sync;
+use crate::verify::Report;
+use crate::wg::GroupIndex;
 
 pub trait ArgProvider {
     fn jobs(&self) -> usize;
     fn dir(&self) -> PathBuf;
     fn verbosity(&self) -> usize;
-    fn path(&self) -> PathBuf;
-    fn rsync_cmd(&self) -> &str;
-    fn rsync_remote(&self) -> &str;
-    fn types(&self) -> Option<Vec<&str>>;
+    fn policy(&self) -> Policy;
+    // Options that only apply to some subcommands default to being absent.
+    fn path(&self) -> PathBuf {
+        PathBuf::new()
+    }
+    fn doc_id(&self) -> &str {
+        ""
+    }
+    fn doc_ids(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn rsync_cmd(&self) -> &str {
+        ""
+    }
+    fn rsync_remote(&self) -> &str {
+        ""
+    }
+    fn formats(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn rfc_index_remote(&self) -> Option<&str> {
+        None
+    }
+    fn errata_remote(&self) -> Option<&str> {
+        None
+    }
+    fn types(&self) -> Option<Vec<&str>> {
+        None
+    }
+    fn active_only(&self) -> bool {
+        false
+    }
+    fn listen(&self) -> &str {
+        ""
+    }
+    fn style(&self) -> &str {
+        ""
+    }
+    fn author(&self) -> Option<&str> {
+        None
+    }
+    fn group(&self) -> Option<&str> {
+        None
+    }
+    fn json(&self) -> bool {
+        false
+    }
+    fn list(&self) -> bool {
+        false
+    }
+    fn files_from(&self) -> Option<&str> {
+        None
+    }
+    fn profile(&self) -> Option<&str> {
+        None
+    }
+    fn profiles(&self) -> PathBuf {
+        PathBuf::new()
+    }
+    fn groups(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn revisions(&self) -> Option<u8> {
+        None
+    }
+    fn includes(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn excludes(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn keep(&self) -> Option<u8> {
+        None
+    }
+    fn replaced(&self) -> bool {
+        false
+    }
+    fn expired(&self) -> Option<i64> {
+        None
+    }
+    fn forget(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn dry_run(&self) -> bool {
+        false
+    }
+    fn delete(&self) -> bool {
+        false
+    }
+    fn timeout(&self) -> Option<u64> {
+        None
+    }
+    fn progress(&self) -> bool {
+        false
+    }
+    fn staged(&self) -> bool {
+        false
+    }
+    fn tags(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn starred(&self) -> bool {
+        false
+    }
+    fn action(&self) -> &str {
+        ""
+    }
+    fn target(&self) -> Option<&str> {
+        None
+    }
+    fn values(&self) -> Vec<&str> {
+        Vec::new()
+    }
+    fn history(&self) -> bool {
+        false
+    }
+    fn limit(&self) -> usize {
+        usize::MAX
+    }
+    fn clear(&self) -> bool {
+        false
+    }
+    fn section(&self) -> &str {
+        ""
+    }
+    fn output(&self) -> &str {
+        ""
+    }
+    fn diff(&self) -> Option<&str> {
+        None
+    }
+    fn kind(&self) -> &str {
+        ""
+    }
+    fn output_dir(&self) -> PathBuf {
+        PathBuf::new()
+    }
+    fn stdout(&self) -> bool {
+        false
+    }
+    fn registry(&self) -> Option<&str> {
+        None
+    }
+    fn statuses(&self) -> Vec<&str> {
+        Vec::new()
+    }
 }
 
 type Cmd = fn(&dyn ArgProvider) -> Result<()>;
@@ -28,9 +192,28 @@ pub struct CmdExec<'a> {
 impl<'a> CmdExec<'a> {
     pub fn init(command: &str, args: &'a dyn ArgProvider) -> Result<Self> {
         let func = match command {
+            "authors" => authors,
+            "browse" => browse,
+            "cite" => cite,
+            "errata" => errata,
+            "extract" => extract,
+            "iana" => iana,
             "index" => index,
+            "note" => note,
+            "prune" => prune,
+            "recent" => recent,
+            "requirements" => requirements,
+            "section" => section,
+            "serve" => serve,
+            "show" => show,
+            "star" => star,
+            "stats" => stats,
             "summary" => summary,
             "sync" => sync,
+            "tag" => tag,
+            "toc" => toc,
+            "verify" => verify,
+            "wg" => wg,
             _ => {
                 return Err(Error::ImplementationNotFound(format!(
                     "Failed to find an implementation for sub-command '{}'",
@@ -46,38 +229,457 @@ impl<'a> CmdExec<'a> {
     }
 }
 
-fn index(args: &dyn ArgProvider) -> Result<()> {
-    let collection = match Collection::from_dir(args.dir()) {
-        Ok(set) => set,
-        Err(e) => return Err(e),
-    };
+fn authors(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let index = AuthorIndex::new(collection, args.jobs());
     let _stdout = stdout();
     #[cfg(not(test))]
     let mut writer = _stdout.lock();
     #[cfg(test)]
     let mut writer = std::io::sink();
-    for result in collection
-        .filter_types(args.types())
-        .newest(1)
+    let name = match args.author() {
+        Some(name) => name,
+        None => {
+            for entry in &index {
+                if writeln!(writer, "{:>5} {}", entry.docs.len(), entry.author.fullname).is_err() {
+                    return Ok(());
+                }
+            }
+            return Ok(());
+        }
+    };
+    let entries = index.find(name);
+    if entries.is_empty() {
+        return Err(Error::DocumentNotFound(format!(
+            "Failed to find any documents by author '{}'",
+            name
+        )));
+    }
+    for entry in entries {
+        let heading = match &entry.author.email {
+            Some(email) => format!("{} <{}>", entry.author.fullname, email),
+            None => entry.author.fullname.to_owned(),
+        };
+        if writeln!(writer, "{}", heading).is_err() {
+            return Ok(());
+        }
+        for doc in &entry.docs {
+            match doc.fmt_line() {
+                Ok(line) => {
+                    if writeln!(writer, "{}", line).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn browse(args: &dyn ArgProvider) -> Result<()> {
+    let pin = Pin::acquire(&args.dir());
+    let dir = pin.path().to_path_buf();
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy())
+        .filter_types(args.types());
+    let history = if args.history() {
+        Some(History::new(&args.dir()))
+    } else {
+        None
+    };
+    browse::run(collection, args.jobs(), history)
+}
+
+fn cite(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let mut docs = Vec::new();
+    for id in args.doc_ids() {
+        let resolved = collection.resolve(id, &overlay);
+        if resolved.is_empty() {
+            return Err(Error::DocumentNotFound(format!(
+                "Failed to find a document matching '{}'",
+                id
+            )));
+        }
+        docs.extend(resolved);
+    }
+    if docs.is_empty() {
+        docs.extend(collection.filter_types(args.types()).newest(1));
+        docs.sort_by(|a, b| a.id().cmp(b.id()));
+    }
+    let style = args.style().to_string();
+    let total = docs.len();
+    let mut entries = Vec::new();
+    for (i, result) in docs
+        .into_iter()
+        .enumerate()
         .with_threads(args.jobs())
-        .map(|doc| doc.fmt_line())
+        .map(move |(i, doc)| (i, cite::fmt_entry(&doc, &style)))
     {
         match result {
-            Ok(line) => {
-                if writeln!(writer, "{}", line).is_err() {
-                    return Ok(());
+            Ok(entry) => entries.push((i, entry)),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    entries.sort();
+    let count = entries.len();
+    let bibliography = cite::fmt_bibliography(
+        entries.into_iter().map(|(_, entry)| entry).collect(),
+        args.style(),
+    );
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    writeln!(writer, "{}", bibliography).ok();
+    partial(total - count, total)
+}
+
+fn errata(args: &dyn ArgProvider) -> Result<()> {
+    let errata = match Errata::from_dir(&snapshot(&args.dir()))? {
+        Some(errata) => errata,
+        None => {
+            return Err(Error::DocumentNotFound(format!(
+                "Failed to find '{}' in the local mirror: fetch it with 'rfz sync --errata'",
+                ERRATA
+            )))
+        }
+    };
+    let id = normalize_id(args.doc_id());
+    let statuses = args.statuses();
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    writeln!(writer, "{}", errata::fmt_counts(&id, errata.get(&id))).ok();
+    for erratum in errata
+        .get(&id)
+        .iter()
+        .filter(|erratum| statuses.is_empty() || statuses.contains(&erratum.status_key()))
+        .collect::<Vec<&Erratum>>()
+    {
+        if writeln!(writer, "\n{}", erratum.fmt_text()).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn extract(args: &dyn ArgProvider) -> Result<()> {
+    let doc = locate(args, args.doc_id())?;
+    let artifacts = extract::extract(doc.id(), &doc.body()?, args.kind());
+    if artifacts.is_empty() {
+        return Err(Error::DocumentNotFound(format!(
+            "Failed to find any {} artifacts in {}",
+            args.kind(),
+            fmt_id(doc.id())
+        )));
+    }
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if args.stdout() {
+        for artifact in artifacts {
+            write!(writer, "{}", artifact.content).ok();
+        }
+        return Ok(());
+    }
+    let dir = args.output_dir();
+    fs::create_dir_all(&dir).map_err(Error::ExtractError)?;
+    for artifact in artifacts {
+        let path = dir.join(&artifact.name);
+        fs::write(&path, artifact.content).map_err(Error::ExtractError)?;
+        writeln!(writer, "{}", path.to_string_lossy()).ok();
+    }
+    Ok(())
+}
+
+fn iana(args: &dyn ArgProvider) -> Result<()> {
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if let Some(name) = args.target() {
+        let doc = locate(args, name)?;
+        let considerations = match Considerations::parse(&doc.sections()?) {
+            Some(considerations) => considerations,
+            None => {
+                return Err(Error::DocumentNotFound(format!(
+                    "Failed to find an IANA Considerations section in {}",
+                    fmt_id(doc.id())
+                )))
+            }
+        };
+        let output = if args.json() {
+            considerations.to_json().to_string()
+        } else {
+            considerations.fmt_table()
+        };
+        writeln!(writer, "{}", output).ok();
+        return Ok(());
+    }
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let index = IanaIndex::new(collection, args.jobs(), &args.dir().join(IANA_CACHE));
+    let entries = match args.registry() {
+        Some(query) => index.find(query),
+        None => index.into_iter().collect(),
+    };
+    if args.json() {
+        writeln!(writer, "{}", IanaIndex::to_json(&entries)).ok();
+        return Ok(());
+    }
+    let query = match args.registry() {
+        Some(query) => query,
+        None => {
+            for entry in entries {
+                if writeln!(writer, "{:>5} {}", entry.docs.len(), entry.name).is_err() {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+    };
+    if entries.is_empty() {
+        return Err(Error::DocumentNotFound(format!(
+            "Failed to find any documents touching registry '{}'",
+            query
+        )));
+    }
+    for entry in entries {
+        if writeln!(writer, "{}", entry.name).is_err() {
+            return Ok(());
+        }
+        for doc in &entry.docs {
+            match doc.fmt_line() {
+                Ok(line) => {
+                    if writeln!(writer, "{}", line).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn index(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let annotations = Annotations::from_dir(&args.dir())?;
+    let mut collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_annotations(&annotations)
+        .with_policy(args.policy());
+    if !args.tags().is_empty() {
+        collection = collection.filter_tags(&args.tags());
+    }
+    if args.starred() {
+        collection = collection.starred();
+    }
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    let newest = collection.newest(1);
+    let wanted = newest.filter_types(args.types());
+    // Drafts are only marked as replaced once every document has been parsed,
+    // so they are held back; everything else is written as soon as it is parsed.
+    let collection = if (&wanted)
+        .into_iter()
+        .any(|doc| doc.id().starts_with("draft"))
+    {
+        newest
+    } else {
+        wanted
+    };
+    let mut processed = Vec::new();
+    let mut failed = HashSet::new();
+    let write = |writer: &mut dyn Write, doc: &Document| {
+        if args.verbosity() > 0 {
+            warn(doc);
+        }
+        match doc.fmt_line() {
+            Ok(line) => writeln!(writer, "{}", line).is_ok(),
+            Err(e) => {
+                eprintln!("{}", e);
+                true
+            }
+        }
+    };
+    for (doc, result) in collection.with_threads(args.jobs()).map(|doc| {
+        let result = doc.ensure_meta().map(|_| ());
+        (doc, result)
+    }) {
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            failed.insert(doc.path().to_owned());
+        } else {
+            let is_type = args.types().is_none_or(|types| doc.is_type(&types));
+            if !doc.id().starts_with("draft") && is_type && !write(&mut writer, &doc) {
+                return Ok(());
+            }
+        }
+        processed.push(doc);
+    }
+    // Failures only count against the documents that would have been printed.
+    let mut docs = processed
+        .into_iter()
+        .collect::<Collection>()
+        .with_lineage()
+        .filter_types(args.types());
+    if args.active_only() {
+        docs = docs.active();
+    }
+    let total = docs.len();
+    let failures = (&docs)
+        .into_iter()
+        .filter(|doc| failed.contains(doc.path()))
+        .count();
+    for doc in &docs {
+        if doc.id().starts_with("draft") && !failed.contains(doc.path()) && !write(&mut writer, doc)
+        {
+            return Ok(());
+        }
+    }
+    partial(failures, total)
+}
+
+fn partial(failures: usize, total: usize) -> Result<()> {
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(Error::PartialFailure(format!(
+            "Failed to process {} of {} documents",
+            failures, total
+        )))
+    }
+}
+
+fn note(args: &dyn ArgProvider) -> Result<()> {
+    let mut annotations = Annotations::from_dir(&args.dir())?;
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    match (args.action(), args.target()) {
+        ("list", Some(id)) => {
+            if let Some(annotation) = annotations.get(id) {
+                for (i, note) in annotation.notes.iter().enumerate() {
+                    writeln!(writer, "{:>3}. {}", i + 1, note).ok();
+                }
+            }
+            Ok(())
+        }
+        ("list", None) => {
+            for (id, annotation) in annotations.iter() {
+                for note in &annotation.notes {
+                    writeln!(writer, "{}: {}", fmt_id(id), note).ok();
                 }
             }
-            Err(e) => eprintln!("{:?}", e),
+            Ok(())
+        }
+        ("add", Some(id)) => {
+            let text = args.values().join(" ");
+            if text.trim().is_empty() {
+                return Err(Error::CliError("A note must not be empty".to_string()));
+            }
+            let id = locate(args, id)?.id().to_owned();
+            annotations.entry(&id).notes.push(text.trim().to_string());
+            annotations.save()
+        }
+        ("rm", Some(id)) => {
+            let notes = &mut annotations.entry(id).notes;
+            match args.values().first() {
+                None => notes.clear(),
+                Some(value) => match usize::from_str(value) {
+                    Ok(n) if n > 0 && n <= notes.len() => {
+                        notes.remove(n - 1);
+                    }
+                    _ => {
+                        return Err(Error::CliError(format!(
+                            "Expected a note number between 1 and {} for {}",
+                            notes.len(),
+                            fmt_id(&normalize_id(id))
+                        )))
+                    }
+                },
+            }
+            annotations.save()
         }
+        _ => Err(Error::CliError(
+            "A document <id> is required to add or remove notes".to_string(),
+        )),
     }
+}
+
+fn stats(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let stats = Stats::new(collection, args.jobs());
+    let output = if args.json() {
+        stats.to_json().to_string()
+    } else {
+        stats.fmt_table()
+    };
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    writeln!(writer, "{}", output).ok();
     Ok(())
 }
 
 fn summary(args: &dyn ArgProvider) -> Result<()> {
     match Document::from_path(args.path()) {
         Some(result) => match result {
-            Ok(doc) => println!("{}", doc.fmt_summary()?),
+            Ok(doc) => {
+                let dir = snapshot(&args.dir());
+                let overlay = Overlay::from_dir(&dir).with_errata(&dir);
+                let annotations = Annotations::from_dir(&args.dir())?;
+                let doc = doc
+                    .with_overlay(&overlay)
+                    .with_annotations(&annotations)
+                    .with_policy(args.policy());
+                let _stdout = stdout();
+                #[cfg(not(test))]
+                let mut writer = _stdout.lock();
+                #[cfg(test)]
+                let mut writer = std::io::sink();
+                if writeln!(writer, "{}", doc.fmt_summary()?).is_err() {
+                    return Ok(());
+                }
+                record(args, &doc);
+                if args.verbosity() > 0 {
+                    warn(&doc);
+                }
+            }
             Err(e) => return Err(e),
         },
         None => {
@@ -90,39 +692,697 @@ fn summary(args: &dyn ArgProvider) -> Result<()> {
     Ok(())
 }
 
-fn sync(args: &dyn ArgProvider) -> Result<()> {
+fn prune(args: &dyn ArgProvider) -> Result<()> {
+    let retention = Retention {
+        keep: args.keep(),
+        replaced: args.replaced(),
+        expired: args.expired(),
+    };
+    if !args.forget().is_empty()
+        && (retention.keep.is_some() || retention.replaced || retention.expired.is_some())
+    {
+        return Err(Error::CliError(
+            "--forget cannot be combined with a retention policy".to_string(),
+        ));
+    }
+    let lock = if args.dry_run() {
+        None
+    } else {
+        Some(Lock::acquire(&args.dir())?)
+    };
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if !args.forget().is_empty() {
+        let names = match prune::forget(&args.dir(), &args.forget(), args.dry_run()) {
+            Ok(names) => names,
+            Err(e) => return Err(Error::PruneError(e)),
+        };
+        for name in names {
+            writeln!(
+                writer,
+                "{} {}",
+                if args.dry_run() {
+                    "Would forget"
+                } else {
+                    "Forgetting"
+                },
+                name
+            )
+            .ok();
+        }
+        drop(lock);
+        return Ok(());
+    }
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let docs = retention.select(&collection, args.jobs());
+    for (doc, reason) in &docs {
+        writeln!(
+            writer,
+            "{} {}: {}",
+            if args.dry_run() {
+                "Would remove"
+            } else {
+                "Removing"
+            },
+            doc.path().to_string_lossy(),
+            reason.describe()
+        )
+        .ok();
+    }
+    if !args.dry_run() {
+        if let Err(e) = prune::remove(&args.dir(), &docs) {
+            return Err(Error::PruneError(e));
+        }
+    }
+    drop(lock);
+    Ok(())
+}
+
+fn recent(args: &dyn ArgProvider) -> Result<()> {
+    let history = History::new(&args.dir());
+    if args.clear() {
+        return history.clear().map_err(Error::HistoryError);
+    }
+    let ids = history.recent().map_err(Error::HistoryError)?;
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let annotations = Annotations::from_dir(&args.dir())?;
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_annotations(&annotations)
+        .with_policy(args.policy())
+        .newest(1);
+    let docs = (&collection)
+        .into_iter()
+        .map(|doc| (doc.id().as_str(), doc))
+        .collect::<HashMap<&str, &Document>>();
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    for doc in ids
+        .iter()
+        .filter_map(|id| docs.get(id.as_str()))
+        .take(args.limit())
+    {
+        match doc.fmt_line() {
+            Ok(line) => {
+                if writeln!(writer, "{}", line).is_err() {
+                    return Ok(());
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+fn record(args: &dyn ArgProvider, doc: &Document) {
+    if args.history() {
+        if let Err(e) = History::new(&args.dir()).record(doc.id()) {
+            eprintln!("{}", Error::HistoryError(e));
+        }
+    }
+}
+
+fn requirements(args: &dyn ArgProvider) -> Result<()> {
+    let doc = locate(args, args.doc_id())?;
+    let found = requirements::extract(&doc.sections()?);
+    let other;
+    let rows = match args.diff() {
+        Some(name) => {
+            other = requirements::extract(&locate(args, name)?.sections()?);
+            requirements::diff(&found, &other)
+        }
+        None => found.iter().map(|r| (None, r)).collect::<Vec<Row>>(),
+    };
+    let output = match args.output() {
+        "csv" => requirements::fmt_csv(&rows),
+        "json" => requirements::to_json(&rows).to_string(),
+        _ => requirements::fmt_table(&rows),
+    };
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if !output.is_empty() {
+        writeln!(writer, "{}", output).ok();
+    }
+    Ok(())
+}
+
+fn section(args: &dyn ArgProvider) -> Result<()> {
+    let doc = locate(args, args.doc_id())?;
+    let sections = doc.sections()?;
+    match Section::find(&sections, args.section()) {
+        Some(section) => {
+            let _stdout = stdout();
+            #[cfg(not(test))]
+            let mut writer = _stdout.lock();
+            #[cfg(test)]
+            let mut writer = std::io::sink();
+            if writeln!(writer, "{}", section.text()).is_err() {
+                return Ok(());
+            }
+            record(args, &doc);
+            Ok(())
+        }
+        None => Err(Error::DocumentNotFound(format!(
+            "Failed to find section '{}' in {}",
+            args.section(),
+            fmt_id(doc.id())
+        ))),
+    }
+}
+
+fn locate(args: &dyn ArgProvider, name: &str) -> Result<Document> {
+    let path = PathBuf::from(name);
+    if path.is_file() {
+        if let Some(result) = Document::from_path(path) {
+            return Ok(result?.with_policy(args.policy()));
+        }
+    }
+    let id = normalize_id(name);
+    let (id, version) = split_revision(&id);
+    let collection = Collection::from_dir(snapshot(&args.dir()))?
+        .with_policy(args.policy())
+        .into_iter()
+        .filter(|doc| doc.id() == id && version.is_none_or(|v| *doc.version() == v))
+        .collect::<Collection>();
+    match collection
+        .newest(1)
+        .into_iter()
+        .min_by(|a, b| a.path().cmp(b.path()))
+    {
+        Some(doc) => Ok(doc),
+        None => Err(Error::DocumentNotFound(format!(
+            "Failed to find a document matching '{}'",
+            name
+        ))),
+    }
+}
+
+fn serve(args: &dyn ArgProvider) -> Result<()> {
+    let pin = Pin::acquire(&args.dir());
+    serve::run(
+        pin.path().to_path_buf(),
+        args.listen(),
+        args.jobs(),
+        args.policy(),
+    )
+}
+
+fn show(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir).with_errata(&dir);
+    let annotations = Annotations::from_dir(&args.dir())?;
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_annotations(&annotations)
+        .with_policy(args.policy())
+        .resolve(args.doc_id(), &overlay);
+    if collection.is_empty() {
+        return Err(Error::DocumentNotFound(format!(
+            "Failed to find a document matching '{}'",
+            args.doc_id()
+        )));
+    }
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    let id = normalize_id(args.doc_id());
+    if let Some(members) = overlay.members(&id) {
+        writeln!(
+            writer,
+            "{}: {}\n",
+            fmt_id(&id),
+            members
+                .iter()
+                .map(|member| fmt_id(member))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+        .ok();
+    }
+    for doc in collection {
+        if writeln!(writer, "{}\n", doc.fmt_summary()?).is_err() {
+            return Ok(());
+        }
+        record(args, &doc);
+        if args.verbosity() > 0 {
+            warn(&doc);
+        }
+    }
+    Ok(())
+}
+
+fn star(args: &dyn ArgProvider) -> Result<()> {
+    let mut annotations = Annotations::from_dir(&args.dir())?;
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    match (args.action(), args.target()) {
+        ("list", None) => {
+            for (id, annotation) in annotations.iter() {
+                if annotation.starred && writeln!(writer, "{}", fmt_id(id)).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ("list", Some(id)) => {
+            let starred = annotations.get(id).is_some_and(|a| a.starred);
+            writeln!(writer, "{}", starred).ok();
+            Ok(())
+        }
+        ("add", Some(id)) => {
+            let id = locate(args, id)?.id().to_owned();
+            annotations.entry(&id).starred = true;
+            annotations.save()
+        }
+        (_, Some(id)) => {
+            annotations.entry(id).starred = false;
+            annotations.save()
+        }
+        _ => Err(Error::CliError(
+            "A document <id> is required to add or remove a star".to_string(),
+        )),
+    }
+}
+
+fn sync(args: &dyn ArgProvider) -> Result<()> {
+    let profile = match args.profile() {
+        Some(name) => Profile::load(&args.profiles(), name)?,
+        None => Profile::default(),
+    }
+    .merge(Profile {
+        types: args
+            .types()
+            .unwrap_or_default()
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+        groups: args.groups().iter().map(|g| g.to_string()).collect(),
+        revisions: args.revisions(),
+        include: args.includes().iter().map(|p| p.to_string()).collect(),
+        exclude: args.excludes().iter().map(|p| p.to_string()).collect(),
+    });
+    let lock = Lock::acquire(&args.dir())?;
+    let result = if args.staged() {
+        let stage = Stage::new(&args.dir())?;
+        match transfer(args, &profile, stage.path()) {
+            Ok(()) => stage.commit(),
+            Err(e) => {
+                stage.abort();
+                Err(e)
+            }
+        }
+    } else {
+        transfer(args, &profile, &snapshot(&args.dir()))
+    };
+    drop(lock);
+    result
+}
+
+fn transfer(args: &dyn ArgProvider, profile: &Profile, target: &Path) -> Result<()> {
+    let mut rules = Vec::new();
+    let pruned = args.dir().join(PRUNED);
+    if pruned.is_file() {
+        rules.push(format!("--exclude-from={}", pruned.to_string_lossy()));
+    }
+    rules.extend(profile.rules(&args.formats()));
+    let mut proc = rsync(args);
+    let mut selected = None;
+    let files_from = if profile.is_selective() {
+        let mut list = rsync(args);
+        list.arg("--list-only")
+            .args(&rules)
+            .arg(args.rsync_remote())
+            .stderr(Stdio::inherit());
+        let output = match list.output() {
+            Ok(output) => output,
+            Err(e) => return Err(Error::SyncError(e)),
+        };
+        sync::check(Ok(output.status))?;
+        let names = parse_listing(&String::from_utf8_lossy(&output.stdout));
+        let overlay = Overlay::from_dir(target);
+        let names = profile.select(names, &overlay);
+        // Written next to the lock file, which keeps other syncs away from it.
+        let path = args.dir().join(SYNC_LIST);
+        fs::remove_file(&path).ok();
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(names.join("\n").as_bytes()));
+        if let Err(e) = written {
+            return Err(Error::SyncError(e));
+        }
+        selected = Some(names);
+        Some(path)
+    } else {
+        args.files_from().map(PathBuf::from)
+    };
+    // rsync cannot delete within a file list, so unselected files are
+    // removed below instead.
+    match (&files_from, args.delete()) {
+        (Some(path), _) => {
+            proc.arg(format!("--files-from={}", path.to_string_lossy()));
+        }
+        (None, true) => {
+            proc.arg("--delete");
+        }
+        (None, false) => (),
+    }
+    proc.args(&rules)
+        .arg("--prune-empty-dirs")
+        .arg(args.rsync_remote())
+        .arg(target);
+    let result = sync::run(proc, args.progress());
+    if let (true, Some(path)) = (profile.is_selective(), files_from) {
+        fs::remove_file(path).ok();
+    }
+    result?;
+    if let (Some(selected), true) = (selected, args.delete()) {
+        delete_unselected(target, &selected, &args.formats(), args.dry_run())?;
+    }
+    if let Some(remote) = args.rfc_index_remote() {
+        let mut proc = rsync(args);
+        proc.arg(remote).arg(target.join(RFC_INDEX));
+        sync::run(proc, args.progress())?;
+    }
+    if let (Some(remote), false) = (args.errata_remote(), args.dry_run()) {
+        errata::fetch(curl(args), remote, &target.join(ERRATA))?;
+    }
+    Ok(())
+}
+
+fn delete_unselected(
+    target: &Path,
+    selected: &[String],
+    formats: &[&str],
+    dry_run: bool,
+) -> Result<()> {
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    let entries = match fs::read_dir(target) {
+        Ok(entries) => entries,
+        Err(e) => return Err(Error::SyncError(e)),
+    };
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            // Like rsync, leave alone the formats that were not synced.
+            formats
+                .iter()
+                .any(|format| name.ends_with(&format!(".{}", format)))
+                && !selected.contains(name)
+                && is_document(&target.join(name))
+        })
+        .collect::<Vec<String>>();
+    names.sort();
+    for name in names {
+        writeln!(writer, "deleting {}", name).ok();
+        if !dry_run {
+            if let Err(e) = fs::remove_file(target.join(&name)) {
+                return Err(Error::SyncError(e));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn tag(args: &dyn ArgProvider) -> Result<()> {
+    let mut annotations = Annotations::from_dir(&args.dir())?;
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if let Some(value) = args
+        .values()
+        .iter()
+        .find(|v| v.is_empty() || v.contains(char::is_whitespace))
+    {
+        return Err(Error::CliError(format!(
+            "Tags must not be empty or contain whitespace: '{}'",
+            value
+        )));
+    }
+    match (args.action(), args.target()) {
+        ("list", Some(id)) => {
+            if let Some(annotation) = annotations.get(id) {
+                for tag in &annotation.tags {
+                    writeln!(writer, "{}", tag).ok();
+                }
+            }
+            Ok(())
+        }
+        ("list", None) => {
+            for (tag, count) in annotations.tags() {
+                if writeln!(writer, "{:>5} {}", count, tag).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ("add", Some(id)) => {
+            let id = locate(args, id)?.id().to_owned();
+            let tags = &mut annotations.entry(&id).tags;
+            tags.extend(args.values().iter().map(|v| v.to_string()));
+            annotations.save()
+        }
+        ("rm", Some(id)) => {
+            let tags = &mut annotations.entry(id).tags;
+            for value in args.values() {
+                tags.remove(value);
+            }
+            annotations.save()
+        }
+        _ => Err(Error::CliError(
+            "A document <id> is required to add or remove tags".to_string(),
+        )),
+    }
+}
+
+fn toc(args: &dyn ArgProvider) -> Result<()> {
+    let doc = locate(args, args.doc_id())?;
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    for section in doc.sections()? {
+        if writeln!(writer, "{}", section.fmt_toc()).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn verify(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let report = Report::from_dir(dir.to_owned(), args.jobs())?;
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    if args.list() {
+        for path in report.refetch(&dir) {
+            if writeln!(writer, "{}", path.to_string_lossy()).is_err() {
+                return Ok(());
+            }
+        }
+        return Ok(());
+    }
+    writeln!(writer, "{}", report.fmt_report()).ok();
+    if report.problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::IntegrityError(format!(
+            "Found {} problems in the local mirror at {:?}",
+            report.problems.len(),
+            args.dir()
+        )))
+    }
+}
+
+fn warn(doc: &Document) {
+    if let Ok(meta) = doc.meta() {
+        for warning in meta.warnings() {
+            eprintln!("{}: {}", doc.path().to_string_lossy(), warning);
+        }
+    }
+}
+
+fn wg(args: &dyn ArgProvider) -> Result<()> {
+    let dir = snapshot(&args.dir());
+    let overlay = Overlay::from_dir(&dir);
+    let collection = Collection::from_dir(dir)?
+        .with_overlay(&overlay)
+        .with_policy(args.policy());
+    let index = GroupIndex::new(collection, args.jobs());
+    let _stdout = stdout();
+    #[cfg(not(test))]
+    let mut writer = _stdout.lock();
+    #[cfg(test)]
+    let mut writer = std::io::sink();
+    let name = match args.group() {
+        Some(name) => name,
+        None => {
+            for group in &index {
+                if writeln!(
+                    writer,
+                    "{:>5} {} ({})",
+                    group.active(),
+                    group.name,
+                    group.stream
+                )
+                .is_err()
+                {
+                    return Ok(());
+                }
+            }
+            return Ok(());
+        }
+    };
+    let group = match index.get(name) {
+        Some(group) => group,
+        None => {
+            return Err(Error::DocumentNotFound(format!(
+                "Failed to find any documents for working group '{}'",
+                name
+            )))
+        }
+    };
+    for doc in group.drafts.iter().chain(group.rfcs.iter()) {
+        match doc.fmt_line() {
+            Ok(line) => {
+                if writeln!(writer, "{}", line).is_err() {
+                    return Ok(());
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+fn rsync(args: &dyn ArgProvider) -> Command {
     let mut proc = Command::new(args.rsync_cmd());
     if args.verbosity() > 0 {
         proc.arg(format!("-{}", "v".repeat(args.verbosity())));
     }
-    proc.arg("--archive")
-        .arg("--compress")
-        .arg("--include=*.html")
-        .arg("--exclude=**")
-        .arg("--prune-empty-dirs")
-        .arg(args.rsync_remote())
-        .arg(args.dir());
-    let status = proc.status();
-    match status {
-        Ok(_) => Ok(()),
-        Err(e) => Err(Error::SyncError(e)),
+    proc.arg("--archive").arg("--compress");
+    if args.dry_run() {
+        proc.arg("--dry-run");
+    }
+    if let Some(timeout) = args.timeout() {
+        proc.arg(format!("--timeout={}", timeout));
+    }
+    proc
+}
+
+fn curl(args: &dyn ArgProvider) -> Command {
+    let mut proc = Command::new("curl");
+    if args.verbosity() > 0 {
+        proc.arg("--verbose");
+    }
+    if args.progress() {
+        proc.arg("--progress-bar");
+    } else {
+        proc.arg("--silent").arg("--show-error");
+    }
+    if let Some(timeout) = args.timeout() {
+        proc.arg("--speed-limit")
+            .arg("1")
+            .arg("--speed-time")
+            .arg(timeout.to_string());
     }
+    proc
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use crate::test::resource_path;
+    use crate::annotate::ANNOTATIONS;
+    use crate::errors::EXIT_SYNC;
+    use crate::mirror::{CURRENT, LOCK};
+    use crate::test::{resource_path, scratch_dir};
 
+    #[derive(Default)]
     struct DummyArgs {
         jobs: Option<usize>,
         dir: Option<PathBuf>,
         verbosity: usize,
+        policy: Policy,
         path: Option<PathBuf>,
+        doc_id: Option<&'static str>,
+        doc_ids: Vec<&'static str>,
         rsync_cmd: Option<String>,
         rsync_remote: Option<String>,
+        formats: Option<Vec<&'static str>>,
+        rfc_index_remote: Option<String>,
+        errata_remote: Option<String>,
         types: Option<Vec<&'static str>>,
+        active_only: bool,
+        listen: Option<&'static str>,
+        style: Option<&'static str>,
+        author: Option<&'static str>,
+        group: Option<&'static str>,
+        json: bool,
+        list: bool,
+        files_from: Option<&'static str>,
+        profile: Option<&'static str>,
+        profiles: Option<PathBuf>,
+        groups: Vec<&'static str>,
+        revisions: Option<u8>,
+        includes: Vec<&'static str>,
+        excludes: Vec<&'static str>,
+        keep: Option<u8>,
+        replaced: bool,
+        expired: Option<i64>,
+        forget: Vec<&'static str>,
+        dry_run: bool,
+        delete: bool,
+        timeout: Option<u64>,
+        progress: bool,
+        staged: bool,
+        tags: Vec<&'static str>,
+        starred: bool,
+        action: Option<&'static str>,
+        target: Option<&'static str>,
+        values: Vec<&'static str>,
+        history: bool,
+        limit: Option<usize>,
+        clear: bool,
+        section: Option<&'static str>,
+        output: Option<&'static str>,
+        diff: Option<&'static str>,
+        kind: Option<&'static str>,
+        output_dir: Option<PathBuf>,
+        stdout: bool,
+        registry: Option<&'static str>,
+        statuses: Vec<&'static str>,
     }
 
     impl ArgProvider for DummyArgs {
@@ -135,18 +1395,153 @@ mod test {
         fn verbosity(&self) -> usize {
             self.verbosity.to_owned()
         }
+        fn policy(&self) -> Policy {
+            self.policy
+        }
         fn path(&self) -> PathBuf {
             self.path.as_ref().unwrap().to_owned()
         }
+        fn doc_id(&self) -> &str {
+            self.doc_id.unwrap()
+        }
+        fn doc_ids(&self) -> Vec<&str> {
+            self.doc_ids.to_owned()
+        }
         fn rsync_cmd(&self) -> &str {
             self.rsync_cmd.as_ref().unwrap()
         }
         fn rsync_remote(&self) -> &str {
             self.rsync_remote.as_ref().unwrap()
         }
+        fn formats(&self) -> Vec<&str> {
+            self.formats.as_ref().unwrap().to_owned()
+        }
+        fn rfc_index_remote(&self) -> Option<&str> {
+            self.rfc_index_remote.as_deref()
+        }
+        fn errata_remote(&self) -> Option<&str> {
+            self.errata_remote.as_deref()
+        }
         fn types(&self) -> Option<Vec<&str>> {
             self.types.to_owned()
         }
+        fn active_only(&self) -> bool {
+            self.active_only
+        }
+        fn listen(&self) -> &str {
+            self.listen.unwrap()
+        }
+        fn style(&self) -> &str {
+            self.style.unwrap()
+        }
+        fn author(&self) -> Option<&str> {
+            self.author
+        }
+        fn group(&self) -> Option<&str> {
+            self.group
+        }
+        fn json(&self) -> bool {
+            self.json
+        }
+        fn list(&self) -> bool {
+            self.list
+        }
+        fn files_from(&self) -> Option<&str> {
+            self.files_from
+        }
+        fn profile(&self) -> Option<&str> {
+            self.profile
+        }
+        fn profiles(&self) -> PathBuf {
+            self.profiles.as_ref().unwrap().to_owned()
+        }
+        fn groups(&self) -> Vec<&str> {
+            self.groups.to_owned()
+        }
+        fn revisions(&self) -> Option<u8> {
+            self.revisions
+        }
+        fn includes(&self) -> Vec<&str> {
+            self.includes.to_owned()
+        }
+        fn excludes(&self) -> Vec<&str> {
+            self.excludes.to_owned()
+        }
+        fn keep(&self) -> Option<u8> {
+            self.keep
+        }
+        fn replaced(&self) -> bool {
+            self.replaced
+        }
+        fn expired(&self) -> Option<i64> {
+            self.expired
+        }
+        fn forget(&self) -> Vec<&str> {
+            self.forget.to_owned()
+        }
+        fn dry_run(&self) -> bool {
+            self.dry_run
+        }
+        fn delete(&self) -> bool {
+            self.delete
+        }
+        fn timeout(&self) -> Option<u64> {
+            self.timeout
+        }
+        fn progress(&self) -> bool {
+            self.progress
+        }
+        fn staged(&self) -> bool {
+            self.staged
+        }
+        fn tags(&self) -> Vec<&str> {
+            self.tags.to_owned()
+        }
+        fn starred(&self) -> bool {
+            self.starred
+        }
+        fn action(&self) -> &str {
+            self.action.unwrap()
+        }
+        fn target(&self) -> Option<&str> {
+            self.target
+        }
+        fn values(&self) -> Vec<&str> {
+            self.values.to_owned()
+        }
+        fn history(&self) -> bool {
+            self.history
+        }
+        fn limit(&self) -> usize {
+            self.limit.unwrap()
+        }
+        fn clear(&self) -> bool {
+            self.clear
+        }
+        fn section(&self) -> &str {
+            self.section.unwrap()
+        }
+        fn output(&self) -> &str {
+            self.output.unwrap()
+        }
+        fn diff(&self) -> Option<&str> {
+            self.diff
+        }
+        fn kind(&self) -> &str {
+            self.kind.unwrap()
+        }
+        fn output_dir(&self) -> PathBuf {
+            self.output_dir.as_ref().unwrap().to_owned()
+        }
+        fn stdout(&self) -> bool {
+            self.stdout
+        }
+        fn registry(&self) -> Option<&str> {
+            self.registry
+        }
+        fn statuses(&self) -> Vec<&str> {
+            self.statuses.to_owned()
+        }
     }
 
     #[test]
@@ -154,11 +1549,321 @@ mod test {
         let args = DummyArgs {
             jobs: Some(2),
             dir: Some(resource_path("")),
-            verbosity: 0,
-            path: None,
-            rsync_cmd: None,
-            rsync_remote: None,
-            types: None,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("index", &args)?;
+        assert!(matches!(exec.run(), Err(Error::PartialFailure(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_dry_run() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            keep: Some(1),
+            expired: Some(30),
+            dry_run: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("prune", &args)?;
+        exec.run()?;
+        assert!(resource_path("draft-ietf-sidrops-rpkimaxlen-04.html").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_cmd() -> Result<()> {
+        let dir = scratch_dir("prune-cmd");
+        for name in &[
+            "draft-ietf-sidrops-rpkimaxlen-04.html",
+            "draft-ietf-sidrops-rpkimaxlen-05.html",
+        ] {
+            fs::copy(resource_path(name), dir.join(name)).unwrap();
+        }
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(dir.to_owned()),
+            keep: Some(1),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("prune", &args)?;
+        exec.run()?;
+        assert!(!dir.join("draft-ietf-sidrops-rpkimaxlen-04.html").exists());
+        assert!(dir.join("draft-ietf-sidrops-rpkimaxlen-05.html").is_file());
+        assert_eq!(
+            fs::read_to_string(dir.join(PRUNED)).unwrap(),
+            "draft-ietf-sidrops-rpkimaxlen-04.html\n"
+        );
+        assert!(!dir.join(LOCK).exists());
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(dir.to_owned()),
+            forget: vec!["draft-ietf-sidrops-rpkimaxlen"],
+            ..DummyArgs::default()
+        };
+        CmdExec::init("prune", &args)?.run()?;
+        assert_eq!(fs::read_to_string(dir.join(PRUNED)).unwrap(), "");
+        let args = DummyArgs {
+            keep: Some(1),
+            ..args
+        };
+        let result = CmdExec::init("prune", &args)?.run();
+        assert!(matches!(result, Err(Error::CliError(_))));
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotation_cmds() -> Result<()> {
+        let dir = scratch_dir("annotation-cmds");
+        fs::copy(resource_path("rfc6468.html"), dir.join("rfc6468.html")).unwrap();
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(dir.to_owned()),
+            action: Some("add"),
+            target: Some("RFC 6468"),
+            ..DummyArgs::default()
+        };
+        for (command, action, values) in &[
+            ("tag", "add", vec!["sieve", "sip"]),
+            ("tag", "rm", vec!["sip"]),
+            ("star", "add", vec![]),
+            ("note", "add", vec!["Read", "this", "first"]),
+            ("note", "add", vec!["Then", "this"]),
+            ("note", "rm", vec!["1"]),
+            ("tag", "list", vec![]),
+        ] {
+            args.action = Some(action);
+            args.values = values.to_owned();
+            CmdExec::init(command, &args)?.run()?;
+        }
+        assert_eq!(
+            fs::read_to_string(dir.join(ANNOTATIONS)).unwrap(),
+            "rfc6468 star\nrfc6468 tag sieve\nrfc6468 note Then this\n"
+        );
+        args.action = Some("rm");
+        args.values = vec!["3"];
+        let result = CmdExec::init("note", &args)?.run();
+        assert!(matches!(result, Err(Error::CliError(_))));
+        args.values = vec!["two words"];
+        let result = CmdExec::init("tag", &args)?.run();
+        assert!(matches!(result, Err(Error::CliError(_))));
+        args.action = Some("add");
+        args.values = vec!["zzz"];
+        args.target = Some("rfc999999");
+        let result = CmdExec::init("tag", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        args.target = None;
+        let result = CmdExec::init("star", &args)?.run();
+        assert!(matches!(result, Err(Error::CliError(_))));
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_tagged() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            tags: vec!["sieve", "keywords"],
+            starred: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("index", &args)?;
+        exec.run()
+    }
+
+    #[test]
+    fn test_recent_cmd() -> Result<()> {
+        let dir = scratch_dir("recent-cmd");
+        for name in &["rfc6468.html", "rfc8174.xml", "rfc8212.xml"] {
+            fs::copy(resource_path(name), dir.join(name)).unwrap();
+        }
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(dir.to_owned()),
+            path: Some(dir.join("rfc6468.html")),
+            doc_id: Some("rfc8174"),
+            history: true,
+            limit: Some(10),
+            ..DummyArgs::default()
+        };
+        CmdExec::init("summary", &args)?.run()?;
+        CmdExec::init("show", &args)?.run()?;
+        args.history = false;
+        args.doc_id = Some("rfc8212");
+        CmdExec::init("show", &args)?.run()?;
+        let history = History::new(&dir);
+        assert_eq!(history.recent().unwrap(), vec!["rfc8174", "rfc6468"]);
+        CmdExec::init("recent", &args)?.run()?;
+        args.clear = true;
+        CmdExec::init("recent", &args)?.run()?;
+        assert!(history.recent().unwrap().is_empty());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_section_cmds() -> Result<()> {
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            doc_id: Some("RFC 6468"),
+            section: Some("2.6"),
+            ..DummyArgs::default()
+        };
+        CmdExec::init("toc", &args)?.run()?;
+        CmdExec::init("section", &args)?.run()?;
+        args.section = Some("9.9");
+        let result = CmdExec::init("section", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        args.doc_id = Some("rfc9999");
+        let result = CmdExec::init("toc", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirements_cmd() -> Result<()> {
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            doc_id: Some("draft-ietf-sidrops-rpkimaxlen-04"),
+            output: Some("table"),
+            ..DummyArgs::default()
+        };
+        for output in &["table", "csv", "json"] {
+            args.output = Some(output);
+            CmdExec::init("requirements", &args)?.run()?;
+        }
+        args.diff = Some("draft-ietf-sidrops-rpkimaxlen");
+        CmdExec::init("requirements", &args)?.run()?;
+        args.diff = Some("draft-ietf-sidrops-rpkimaxlen-03");
+        let result = CmdExec::init("requirements", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_cmd() -> Result<()> {
+        let dir = scratch_dir("extract");
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            doc_id: Some(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/extract/rfc9999.txt"
+            )),
+            kind: Some("yang"),
+            output_dir: Some(dir.to_owned()),
+            ..DummyArgs::default()
+        };
+        CmdExec::init("extract", &args)?.run()?;
+        assert!(dir.join("ietf-widget@2024-04-01.yang").is_file());
+        args.kind = Some("abnf");
+        args.stdout = true;
+        CmdExec::init("extract", &args)?.run()?;
+        assert!(!dir.join("rfc9999.abnf").exists());
+        args.doc_id = Some("rfc6468");
+        let result = CmdExec::init("extract", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_iana_cmd() -> Result<()> {
+        let dir = scratch_dir("iana-cmd");
+        for name in &[
+            "rfc6468.html",
+            "rfc8212.xml",
+            "draft-ietf-sidrops-rpkimaxlen-05.html",
+        ] {
+            fs::copy(resource_path(name), dir.join(name)).unwrap();
+        }
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(dir.to_owned()),
+            target: Some("rfc6468"),
+            ..DummyArgs::default()
+        };
+        CmdExec::init("iana", &args)?.run()?;
+        args.json = true;
+        CmdExec::init("iana", &args)?.run()?;
+        args.target = Some("rfc8212");
+        CmdExec::init("iana", &args)?.run()?;
+        args.target = Some("draft-ietf-sidrops-rpkimaxlen");
+        let result = CmdExec::init("iana", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        args.target = None;
+        CmdExec::init("iana", &args)?.run()?;
+        args.json = false;
+        CmdExec::init("iana", &args)?.run()?;
+        args.registry = Some("sieve");
+        CmdExec::init("iana", &args)?.run()?;
+        args.registry = Some("bgp path attributes");
+        let result = CmdExec::init("iana", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        assert!(dir.join(IANA_CACHE).is_file());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_errata_cmd() -> Result<()> {
+        let mut args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            doc_id: Some("RFC 6468"),
+            ..DummyArgs::default()
+        };
+        CmdExec::init("errata", &args)?.run()?;
+        args.statuses = vec!["verified", "reported"];
+        CmdExec::init("errata", &args)?.run()?;
+        args.doc_id = Some("rfc8212");
+        CmdExec::init("errata", &args)?.run()?;
+        args.dir = Some(resource_path("missing"));
+        let result = CmdExec::init("errata", &args)?.run();
+        assert!(matches!(result, Err(Error::DocumentNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_lenient() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            verbosity: 1,
+            policy: Policy::KeepFirst,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("index", &args)?;
+        exec.run()
+    }
+
+    #[test]
+    fn test_index_active_only() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            types: Some(vec!["draft"]),
+            active_only: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("index", &args)?;
+        assert!(matches!(exec.run(), Err(Error::PartialFailure(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_filtered_failures() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(2),
+            dir: Some(resource_path("")),
+            types: Some(vec!["draft-ietf"]),
+            active_only: true,
+            ..DummyArgs::default()
         };
         let exec = CmdExec::init("index", &args)?;
         exec.run()
@@ -167,43 +1872,330 @@ mod test {
     #[test]
     fn test_summary_cmd() -> Result<()> {
         let args = DummyArgs {
-            jobs: None,
-            dir: None,
-            verbosity: 0,
+            dir: Some(resource_path("")),
             path: Some(resource_path("rfc6468.html")),
-            rsync_cmd: None,
-            rsync_remote: None,
-            types: None,
+            ..DummyArgs::default()
         };
         let exec = CmdExec::init("summary", &args)?;
         exec.run()
     }
 
     #[test]
-    fn test_sync_cmd() -> Result<()> {
+    fn test_show_cmd() -> Result<()> {
+        let args = DummyArgs {
+            dir: Some(resource_path("")),
+            doc_id: Some("bcp14"),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("show", &args)?;
+        exec.run()
+    }
+
+    #[test]
+    fn test_cite_cmd() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(1),
+            dir: Some(resource_path("")),
+            doc_ids: vec!["bcp14", "rfc8212"],
+            style: Some("xml2rfc"),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("cite", &args)?;
+        exec.run()
+    }
+
+    #[test]
+    fn test_cite_batch() -> Result<()> {
+        let args = DummyArgs {
+            jobs: Some(1),
+            dir: Some(resource_path("")),
+            types: Some(vec!["rfc"]),
+            style: Some("csl-json"),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("cite", &args)?;
+        exec.run()
+    }
+
+    #[test]
+    fn test_authors_cmd() -> Result<()> {
+        for author in &[None, Some("snijders")] {
+            let args = DummyArgs {
+                jobs: Some(1),
+                dir: Some(resource_path("")),
+                author: *author,
+                ..DummyArgs::default()
+            };
+            let exec = CmdExec::init("authors", &args)?;
+            exec.run()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_wg_cmd() -> Result<()> {
+        for group in &[None, Some("grow"), Some("sidrops")] {
+            let args = DummyArgs {
+                jobs: Some(1),
+                dir: Some(resource_path("")),
+                group: *group,
+                ..DummyArgs::default()
+            };
+            let exec = CmdExec::init("wg", &args)?;
+            exec.run()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_cmd() -> Result<()> {
+        for json in &[false, true] {
+            let args = DummyArgs {
+                jobs: Some(1),
+                dir: Some(resource_path("")),
+                json: *json,
+                ..DummyArgs::default()
+            };
+            let exec = CmdExec::init("stats", &args)?;
+            exec.run()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_cmd() -> Result<()> {
+        for list in &[false, true] {
+            let args = DummyArgs {
+                jobs: Some(1),
+                dir: Some(resource_path("")),
+                list: *list,
+                ..DummyArgs::default()
+            };
+            let exec = CmdExec::init("verify", &args)?;
+            match (list, exec.run()) {
+                (true, Ok(())) | (false, Err(Error::IntegrityError(_))) => {}
+                (_, result) => panic!("Unexpected verify result {:?}", result),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_show_not_found() {
         let args = DummyArgs {
-            jobs: None,
             dir: Some(resource_path("")),
+            doc_id: Some("rfc9999"),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("show", &args).unwrap();
+        match exec.run() {
+            Err(Error::DocumentNotFound(_)) => (),
+            _ => panic!("Expected DocumentNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_sync_cmd() -> Result<()> {
+        let dir = scratch_dir("sync-cmd");
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
             verbosity: 2,
-            path: None,
             rsync_cmd: Some(String::from("/bin/true")),
             rsync_remote: Some(String::from("rsync.example.com::dummy")),
-            types: None,
+            formats: Some(vec!["html", "xml"]),
+            rfc_index_remote: Some(String::from("rsync.example.com::dummy/rfc-index.xml")),
+            ..DummyArgs::default()
         };
         let exec = CmdExec::init("sync", &args)?;
-        exec.run()
+        exec.run()?;
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sync_profile() -> Result<()> {
+        let dir = scratch_dir("sync-profile");
+        let victim = dir.join("victim");
+        fs::write(&victim, "keep").unwrap();
+        std::os::unix::fs::symlink(&victim, dir.join(SYNC_LIST)).unwrap();
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/rsync"
+            ))),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html"]),
+            profile: Some("sidrops"),
+            profiles: Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/profiles"
+            ))),
+            dry_run: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        exec.run()?;
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+        assert!(!dir.join(SYNC_LIST).exists());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_delete() -> Result<()> {
+        let dir = scratch_dir("sync-delete");
+        for name in [
+            "draft-ietf-sidrops-rpkimaxlen-04.html",
+            "rfc6468.html",
+            "rfc6468.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/rsync"
+            ))),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html"]),
+            profile: Some("sidrops"),
+            profiles: Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/profiles"
+            ))),
+            delete: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        exec.run()?;
+        let argv = fs::read_to_string(dir.join(".rsync-argv")).unwrap();
+        assert!(argv.lines().any(|arg| arg.starts_with("--files-from=")));
+        assert!(!argv.lines().any(|arg| arg == "--delete"));
+        assert!(!dir.join("draft-ietf-sidrops-rpkimaxlen-04.html").exists());
+        assert!(dir.join("rfc6468.html").is_file());
+        assert!(dir.join("rfc6468.txt").is_file());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_delete_xml() -> Result<()> {
+        let dir = scratch_dir("sync-delete-xml");
+        for name in ["rfc8212.xml", RFC_INDEX, ERRATA] {
+            fs::copy(resource_path(name), dir.join(name)).unwrap();
+        }
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/rsync"
+            ))),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html", "xml"]),
+            types: Some(vec!["draft"]),
+            delete: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        exec.run()?;
+        assert!(!dir.join("rfc8212.xml").exists());
+        assert!(dir.join(RFC_INDEX).is_file());
+        assert!(dir.join(ERRATA).is_file());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_staged() -> Result<()> {
+        let dir = scratch_dir("sync-staged");
+        fs::write(dir.join("rfc6468.html"), "").unwrap();
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/rsync"
+            ))),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html"]),
+            profile: Some("sidrops"),
+            profiles: Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/profiles"
+            ))),
+            dry_run: true,
+            staged: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        exec.run()?;
+        assert!(dir.join(CURRENT).is_dir());
+        assert!(snapshot(&dir).join("rfc6468.html").is_file());
+        assert!(!dir.join(LOCK).exists());
+        let lock = Lock::acquire(&dir)?;
+        assert!(matches!(exec.run(), Err(Error::LockError(_))));
+        drop(lock);
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_unknown_profile() -> Result<()> {
+        let dir = scratch_dir("sync-unknown-profile");
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/rsync"
+            ))),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html"]),
+            profile: Some("nope"),
+            profiles: Some(PathBuf::from(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/sync/profiles"
+            ))),
+            dry_run: true,
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        assert!(matches!(exec.run(), Err(Error::CliError(_))));
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_failure() -> Result<()> {
+        let dir = scratch_dir("sync-failure");
+        let args = DummyArgs {
+            dir: Some(dir.to_owned()),
+            verbosity: 2,
+            rsync_cmd: Some(String::from("/bin/false")),
+            rsync_remote: Some(String::from("rsync.example.com::dummy")),
+            formats: Some(vec!["html", "xml"]),
+            rfc_index_remote: Some(String::from("rsync.example.com::dummy/rfc-index.xml")),
+            ..DummyArgs::default()
+        };
+        let exec = CmdExec::init("sync", &args)?;
+        match exec.run() {
+            Err(e @ Error::SyncError(_)) => assert_eq!(e.exit_code(), EXIT_SYNC),
+            _ => panic!("Expected sync to fail"),
+        }
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
     }
 
     #[test]
     fn test_not_implemented() {
         let args = DummyArgs {
-            jobs: None,
-            dir: None,
-            verbosity: 0,
-            path: None,
-            rsync_cmd: None,
-            rsync_remote: None,
-            types: None,
+            ..DummyArgs::default()
         };
         match CmdExec::init("invalid", &args) {
             Err(Error::ImplementationNotFound(_)) => (),
@@ -214,13 +2206,8 @@ mod test {
     #[test]
     fn test_document_not_found() {
         let args = DummyArgs {
-            jobs: None,
-            dir: None,
-            verbosity: 0,
             path: Some(resource_path("not-found")),
-            rsync_cmd: None,
-            rsync_remote: None,
-            types: None,
+            ..DummyArgs::default()
         };
         let exec = CmdExec::init("summary", &args).unwrap();
         match exec.run() {
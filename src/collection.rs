@@ -1,11 +1,19 @@
-use std::collections::{hash_map, BTreeMap, HashMap};
+use std::collections::{btree_map, BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
 use std::fs;
+use std::iter::FromIterator;
 use std::path::PathBuf;
 use std::slice;
 use std::vec;
 
-use crate::document::Document;
+use pipeliner::Pipeline;
+
+use crate::annotate::Annotations;
+use crate::document::{normalize_id, split_revision, Document, DraftState, Policy};
 use crate::errors::{Error, Result};
+use crate::overlay::{Overlay, RFC_INDEX};
+
+pub const TYPES: &[&str] = &["draft", "rfc", "bcp", "std"];
 
 #[derive(Clone)]
 pub struct Collection(Vec<Document>);
@@ -22,14 +30,11 @@ impl Collection {
                 Ok(e) => e.path(),
                 Err(_) => continue,
             };
-            if !doc_path.is_file() {
+            if !doc_path.is_file() || doc_path.file_name() == Some(OsStr::new(RFC_INDEX)) {
                 continue;
             }
             let doc = match Document::from_path(doc_path) {
-                Some(result) => match result {
-                    Ok(doc) => doc,
-                    Err(e) => return Err(e),
-                },
+                Some(result) => result?,
                 None => continue,
             };
             collection.push(doc);
@@ -37,15 +42,66 @@ impl Collection {
         Ok(Collection(collection))
     }
 
+    pub fn with_overlay(self, overlay: &Overlay) -> Self {
+        Collection(
+            self.into_iter()
+                .map(|doc| doc.with_overlay(overlay))
+                .collect(),
+        )
+    }
+
+    pub fn with_annotations(self, annotations: &Annotations) -> Self {
+        Collection(
+            self.into_iter()
+                .map(|doc| doc.with_annotations(annotations))
+                .collect(),
+        )
+    }
+
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Collection(
+            self.into_iter()
+                .map(|doc| doc.with_policy(policy))
+                .collect(),
+        )
+    }
+
+    pub fn with_meta(self, jobs: usize) -> Self {
+        self.with_threads(jobs)
+            .map(|doc| match doc.ensure_meta() {
+                Ok(_) => Some(doc),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    None
+                }
+            })
+            .collect::<Vec<Option<Document>>>()
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
     pub fn newest(&self, count: u8) -> Self {
         self.to_map().newest(count)
     }
 
+    pub fn superseded(&self, count: u8) -> Self {
+        let newest = self
+            .newest(count)
+            .into_iter()
+            .map(|doc| (doc.id().to_owned(), *doc.version()))
+            .collect::<HashSet<(String, i8)>>();
+        self.into_iter()
+            .filter(|doc| !newest.contains(&(doc.id().to_owned(), *doc.version())))
+            .cloned()
+            .collect()
+    }
+
     pub fn filter_types(&self, types: Option<Vec<&str>>) -> Self {
         match types {
             Some(types) => Collection(
                 self.into_iter()
-                    .filter(|&doc| types.iter().any(|t| doc.id().starts_with(t)))
+                    .filter(|&doc| doc.is_type(&types))
                     .map(|doc| doc.to_owned())
                     .collect(),
             ),
@@ -53,18 +109,105 @@ impl Collection {
         }
     }
 
-    fn to_map(&self) -> CollectionMap {
+    pub fn resolve(&self, id: &str, overlay: &Overlay) -> Self {
+        let id = normalize_id(id);
+        if let (id, Some(version)) = split_revision(&id) {
+            return self
+                .into_iter()
+                .filter(|doc| doc.id() == id && *doc.version() == version)
+                .cloned()
+                .collect::<Collection>()
+                .newest(1);
+        }
+        let ids = match overlay.members(&id) {
+            Some(members) => members.into_iter().cloned().collect(),
+            None => vec![id],
+        };
+        let mut collection = self
+            .newest(1)
+            .into_iter()
+            .filter(|doc| ids.contains(doc.id()))
+            .collect::<Vec<Document>>();
+        collection.sort_by(|a, b| a.id().cmp(b.id()));
+        Collection(collection)
+    }
+
+    pub fn with_lineage(self) -> Self {
+        let lineage = self.lineage();
+        Collection(
+            self.into_iter()
+                .map(|doc| {
+                    let replaced_by = lineage.get(doc.id()).cloned();
+                    doc.with_replaced_by(replaced_by.as_ref())
+                })
+                .collect(),
+        )
+    }
+
+    pub fn active(&self) -> Self {
+        self.into_iter()
+            .filter(|doc| {
+                !matches!(
+                    doc.state(),
+                    Some(DraftState::Expired | DraftState::Replaced(_))
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn filter_tags(&self, tags: &[&str]) -> Self {
+        self.into_iter()
+            .filter(|doc| match doc.annotation() {
+                Some(annotation) => tags.iter().any(|&tag| annotation.tags.contains(tag)),
+                None => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn starred(&self) -> Self {
+        self.into_iter()
+            .filter(|doc| {
+                doc.annotation()
+                    .is_some_and(|annotation| annotation.starred)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn lineage(&self) -> HashMap<String, String> {
+        let mut lineage = HashMap::new();
+        for doc in self {
+            if let Ok(meta) = doc.meta() {
+                for id in meta.values("Relation.Replaces") {
+                    lineage.insert(id.to_owned(), doc.id().to_owned());
+                }
+            }
+        }
+        lineage
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn to_map(&self) -> CollectionMap<'_> {
         let mut map = HashMap::new();
         for doc in self {
-            match map.entry(doc.id()) {
-                hash_map::Entry::Vacant(e) => {
-                    let mut map = BTreeMap::new();
-                    map.insert(doc.version(), doc);
-                    e.insert(map);
+            let versions = map.entry(doc.id()).or_insert_with(BTreeMap::new);
+            match versions.entry(doc.version()) {
+                btree_map::Entry::Vacant(e) => {
+                    e.insert(doc);
                 }
-                hash_map::Entry::Occupied(mut e) => {
-                    let map = e.get_mut();
-                    map.insert(doc.version(), doc);
+                btree_map::Entry::Occupied(mut e) => {
+                    if doc.precedence() < e.get().precedence() {
+                        e.insert(doc);
+                    }
                 }
             };
         }
@@ -72,6 +215,12 @@ impl Collection {
     }
 }
 
+impl FromIterator<Document> for Collection {
+    fn from_iter<I: IntoIterator<Item = Document>>(iter: I) -> Self {
+        Collection(iter.into_iter().collect())
+    }
+}
+
 impl IntoIterator for Collection {
     type Item = Document;
     type IntoIter = vec::IntoIter<Document>;
@@ -86,7 +235,7 @@ impl<'a> IntoIterator for &'a Collection {
     type IntoIter = slice::Iter<'a, Document>;
 
     fn into_iter(self) -> Self::IntoIter {
-        (&self.0).iter()
+        self.0.iter()
     }
 }
 
@@ -114,7 +263,7 @@ mod test {
     fn test_construct_collection() -> Result<()> {
         let path = resource_path("");
         let collection = Collection::from_dir(path)?;
-        assert_eq!(collection.into_iter().count(), 4);
+        assert_eq!(collection.into_iter().count(), 7);
         Ok(())
     }
 
@@ -122,7 +271,42 @@ mod test {
     fn test_newest_collection() -> Result<()> {
         let path = resource_path("");
         let newest = Collection::from_dir(path)?.newest(1);
-        assert_eq!(newest.into_iter().count(), 3);
+        assert_eq!(newest.into_iter().count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_precedence() -> Result<()> {
+        let path = resource_path("");
+        let docs = Collection::from_dir(path)?
+            .into_iter()
+            .filter(|doc| doc.id() == "rfc6468")
+            .collect::<Vec<Document>>();
+        assert_eq!(docs.len(), 2);
+        for docs in [docs.clone(), docs.into_iter().rev().collect()] {
+            let newest = Collection(docs).newest(1);
+            assert_eq!(
+                newest
+                    .into_iter()
+                    .map(|doc| doc.path().file_name().unwrap().to_owned())
+                    .collect::<Vec<_>>(),
+                vec!["rfc6468.html"]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_superseded_collection() -> Result<()> {
+        let path = resource_path("");
+        let superseded = Collection::from_dir(path)?.superseded(1);
+        assert_eq!(
+            superseded
+                .into_iter()
+                .map(|doc| doc.path().file_name().unwrap().to_owned())
+                .collect::<Vec<_>>(),
+            vec!["draft-ietf-sidrops-rpkimaxlen-04.html"]
+        );
         Ok(())
     }
 
@@ -131,7 +315,63 @@ mod test {
         let path = resource_path("");
         let types = Some(vec!["rfc", "bcp"]);
         let filtered = Collection::from_dir(path)?.filter_types(types);
-        assert_eq!(filtered.into_iter().count(), 1);
+        assert_eq!(filtered.into_iter().count(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_series() -> Result<()> {
+        let path = resource_path("");
+        let overlay = Overlay::from_dir(&path);
+        let collection = Collection::from_dir(path)?.with_overlay(&overlay);
+        let bcp = collection.filter_types(Some(vec!["bcp"]));
+        assert_eq!(bcp.into_iter().count(), 1);
+        let resolved = collection.resolve("BCP 14", &overlay);
+        assert_eq!(
+            resolved
+                .into_iter()
+                .map(|doc| doc.id().to_owned())
+                .collect::<Vec<String>>(),
+            vec!["rfc8174"]
+        );
+        assert!(collection.resolve("bcp99", &overlay).is_empty());
+        let draft = collection.resolve("draft-ietf-sidrops-rpkimaxlen-04", &overlay);
+        assert_eq!(
+            draft
+                .into_iter()
+                .map(|doc| doc.path().file_name().unwrap().to_owned())
+                .collect::<Vec<_>>(),
+            vec!["draft-ietf-sidrops-rpkimaxlen-04.html"]
+        );
+        assert!(collection
+            .resolve("draft-ietf-sidrops-rpkimaxlen-03", &overlay)
+            .is_empty());
+        assert_eq!(
+            collection.resolve("rfc6468", &overlay).into_iter().count(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotation_filters() -> Result<()> {
+        let path = resource_path("");
+        let annotations = Annotations::from_dir(&path)?;
+        let collection = Collection::from_dir(path)?.with_annotations(&annotations);
+        let ids = |collection: Collection| {
+            let mut ids = collection
+                .into_iter()
+                .map(|doc| doc.id().to_owned())
+                .collect::<Vec<String>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(collection.filter_tags(&["sieve", "keywords"])),
+            vec!["rfc6468", "rfc6468", "rfc8174"]
+        );
+        assert!(collection.filter_tags(&["missing"]).is_empty());
+        assert_eq!(ids(collection.starred()), vec!["rfc6468", "rfc6468"]);
         Ok(())
     }
 
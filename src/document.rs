@@ -1,60 +1,244 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ansi_term::Colour;
-use kuchiki::traits::*;
 use lazycell::AtomicLazyCell;
 
+use crate::annotate::{Annotation, Annotations};
 use crate::errors::{Error, Result};
+use crate::overlay::Overlay;
+use crate::section::Section;
 
-const SELECTOR: &str = "head>meta";
+mod html;
+mod text;
+mod xml;
 
-const PREFIX: &str = "DC.";
+const MULTIVALUED: &[&str] = &[
+    "Creator",
+    "Creator.Organization",
+    "Subject",
+    "Relation.Replaces",
+    "Relation.Obsoletes",
+    "Relation.Updates",
+    "Relation.ObsoletedBy",
+    "Relation.UpdatedBy",
+    "Relation.IsPartOf",
+    "Relation.HasPart",
+    "Relation.SeeAlso",
+];
 
-const MULTIVALUED: &[&str] = &["Creator", "Relation.Replaces"];
+pub const STATUS: &[(&str, &str)] = &[
+    ("Relation.ObsoletedBy", "Obsoleted by"),
+    ("Relation.UpdatedBy", "Updated by"),
+];
+
+pub const ERRATA_COUNTS: &[(&str, &str)] = &[
+    ("Errata.Verified", "verified"),
+    ("Errata.Reported", "reported"),
+];
+
+pub const MONTHS: &[&str] = &[
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const EXPIRY_DAYS: i64 = 185;
+
+// In order of precedence, when a document is present in several formats.
+const BACKENDS: &[&dyn Backend] = &[&xml::Xml, &html::Html, &text::Text];
+
+pub trait Backend: fmt::Debug + Sync {
+    fn suffix(&self) -> &'static str;
+    fn parse(&self, doc: &Document) -> Result<Metadata>;
+    fn body(&self, doc: &Document) -> Result<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stream {
+    Ietf,
+    Irtf,
+    Iab,
+    Individual,
+}
+
+impl fmt::Display for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Stream::Ietf => "ietf",
+            Stream::Irtf => "irtf",
+            Stream::Iab => "iab",
+            Stream::Individual => "individual",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Date {
+    pub year: u16,
+    pub month: Option<usize>,
+    pub day: Option<u8>,
+}
+
+impl Date {
+    pub fn parse(date: &str) -> Option<Self> {
+        let parts = date.trim().split('-').collect::<Vec<&str>>();
+        if let [year, month, day] = parts.as_slice() {
+            return Some(Date {
+                year: year.parse().ok()?,
+                month: month.parse().ok().filter(|m| (1..=12).contains(m)),
+                day: day.parse().ok(),
+            });
+        }
+        let mut year = None;
+        let mut month = None;
+        let mut day = None;
+        for word in date
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+        {
+            if word.len() == 4 && word.chars().all(|c| c.is_ascii_digit()) {
+                year = word.parse().ok();
+            } else if word.len() < 3 && word.chars().all(|c| c.is_ascii_digit()) {
+                day = word.parse().ok();
+            } else if word.len() >= 3 {
+                month = MONTHS
+                    .iter()
+                    .position(|name| name.to_lowercase().starts_with(&word.to_lowercase()))
+                    .map(|i| i + 1)
+                    .or(month);
+            }
+        }
+        Some(Date {
+            year: year?,
+            month,
+            day,
+        })
+    }
+
+    pub fn month_name(&self) -> Option<&'static str> {
+        self.month.map(|month| MONTHS[month - 1])
+    }
+
+    pub fn days(&self) -> i64 {
+        let year = i64::from(self.year);
+        let month = self.month.unwrap_or(1) as i64;
+        let day = i64::from(self.day.unwrap_or(1));
+        let (year, month) = if month <= 2 {
+            (year - 1, month + 9)
+        } else {
+            (year, month - 3)
+        };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy = (153 * month + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DraftState {
+    Active,
+    Expired,
+    Replaced(String),
+}
 
 #[derive(Debug, Clone)]
 pub struct Document {
     id: String,
     version: i8,
+    stream: Option<Stream>,
+    group: Option<String>,
+    short_name: Option<String>,
     path: PathBuf,
+    backend: &'static dyn Backend,
+    overlay: Option<Metadata>,
+    annotation: Option<Annotation>,
+    policy: Policy,
+    replaced_by: Option<String>,
     meta: AtomicLazyCell<Metadata>,
 }
 
 impl Document {
     pub fn from_path(path: PathBuf) -> Option<Result<Document>> {
-        let file_name = match path.file_name() {
-            Some(name) => match name.to_str() {
-                Some(name) => name,
-                None => return None,
-            },
-            None => return None,
-        };
-        let (id, version) = match file_name.strip_suffix(".html") {
-            Some(name) => {
-                let mut split = name.rsplitn(2, '-').collect::<Vec<&str>>();
-                split.reverse();
-                (
-                    split.get(0).unwrap().to_string(),
-                    -i8::from_str(split.get(1).unwrap_or(&"").to_owned()).unwrap_or(0),
-                )
-            }
-            None => return None,
+        let file_name = path.file_name()?.to_str()?;
+        let (name, backend) = BACKENDS.iter().find_map(|backend| {
+            file_name
+                .strip_suffix(backend.suffix())
+                .map(|name| (name, *backend))
+        })?;
+        let mut split = name.rsplitn(2, '-').collect::<Vec<&str>>();
+        split.reverse();
+        let id = split.first().unwrap().to_string();
+        let version = -i8::from_str(split.get(1).unwrap_or(&"")).unwrap_or(0);
+        let (stream, group, short_name) = match parse_draft_name(&id) {
+            Some((stream, group, short_name)) => (Some(stream), group, Some(short_name)),
+            None => (None, None, None),
         };
         Some(Ok(Document {
             id,
             version,
+            stream,
+            group,
+            short_name,
             path,
+            backend,
+            overlay: None,
+            annotation: None,
+            policy: Policy::Strict,
+            replaced_by: None,
             meta: AtomicLazyCell::new(),
         }))
     }
 
+    pub fn with_overlay(mut self, overlay: &Overlay) -> Self {
+        self.overlay = overlay.get(&self.id).cloned();
+        self
+    }
+
+    pub fn with_annotations(mut self, annotations: &Annotations) -> Self {
+        self.annotation = annotations.get(&self.id).cloned();
+        self
+    }
+
+    pub fn annotation(&self) -> Option<&Annotation> {
+        self.annotation.as_ref()
+    }
+
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_replaced_by(mut self, id: Option<&String>) -> Self {
+        self.replaced_by = id.cloned();
+        self
+    }
+
     pub fn ensure_meta(&self) -> Result<&Self> {
         if !self.meta.filled() {
-            let html = kuchiki::parse_html().from_utf8().from_file(&self.path)?;
-            let meta = Metadata::from_html(html)?;
+            let mut meta = self
+                .backend
+                .parse(self)
+                .map_err(|e| e.in_document(&self.path))?;
+            if let Some(overlay) = &self.overlay {
+                meta.merge(overlay);
+            }
             match self.meta.fill(meta) {
                 Ok(()) => {}
                 Err(val) => {
@@ -76,12 +260,121 @@ impl Document {
         &self.version
     }
 
+    pub fn stream(&self) -> Option<Stream> {
+        self.stream
+    }
+
+    pub fn group(&self) -> Option<&String> {
+        self.group.as_ref()
+    }
+
+    pub fn short_name(&self) -> Option<&String> {
+        self.short_name.as_ref()
+    }
+
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
 
+    pub fn precedence(&self) -> usize {
+        BACKENDS
+            .iter()
+            .position(|backend| backend.suffix() == self.backend.suffix())
+            .unwrap_or(BACKENDS.len())
+    }
+
+    fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    fn urn(&self) -> String {
+        match self.id.strip_prefix("rfc") {
+            Some(num) => format!("urn:ietf:rfc:{}", num),
+            None => format!(
+                "urn:ietf:id:{}",
+                self.id.strip_prefix("draft-").unwrap_or(&self.id)
+            ),
+        }
+    }
+
     pub fn meta(&self) -> Result<&Metadata> {
-        Ok(&self.ensure_meta()?.meta.borrow().unwrap())
+        Ok(self.ensure_meta()?.meta.borrow().unwrap())
+    }
+
+    pub fn body(&self) -> Result<String> {
+        self.backend
+            .body(self)
+            .map_err(|e| e.in_document(&self.path))
+    }
+
+    pub fn sections(&self) -> Result<Vec<Section>> {
+        Ok(Section::parse(&self.body()?))
+    }
+
+    pub fn title(&self) -> Result<String> {
+        Ok(self
+            .meta()?
+            .values("Title")
+            .first()
+            .map_or_else(String::new, |title| title.to_string()))
+    }
+
+    pub fn references(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for id in cited_ids(&self.body()?) {
+            if &id != self.id() && !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    pub fn state(&self) -> Option<DraftState> {
+        self.state_on(today())
+    }
+
+    fn state_on(&self, today: i64) -> Option<DraftState> {
+        if !self.id.starts_with("draft") {
+            return None;
+        }
+        if let Some(id) = &self.replaced_by {
+            return Some(DraftState::Replaced(id.to_owned()));
+        }
+        let issued = self
+            .meta()
+            .ok()?
+            .values("Date.Issued")
+            .first()
+            .and_then(|date| Date::parse(date));
+        match issued {
+            Some(date) if date.days() + EXPIRY_DAYS < today => Some(DraftState::Expired),
+            _ => Some(DraftState::Active),
+        }
+    }
+
+    pub fn days_expired(&self) -> Option<i64> {
+        self.days_expired_on(today())
+    }
+
+    fn days_expired_on(&self, today: i64) -> Option<i64> {
+        if self.state_on(today)? != DraftState::Expired {
+            return None;
+        }
+        let issued = Date::parse(self.meta().ok()?.values("Date.Issued").first()?)?;
+        Some(today - issued.days() - EXPIRY_DAYS)
+    }
+
+    pub fn series(&self) -> Vec<&String> {
+        match &self.overlay {
+            Some(overlay) => overlay.values("Relation.IsPartOf"),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn is_type(&self, types: &[&str]) -> bool {
+        types.iter().any(|t| {
+            self.id.starts_with(t) || self.series().iter().any(|series| series.starts_with(t))
+        })
     }
 
     pub fn fmt_line(&self) -> Result<String> {
@@ -92,11 +385,32 @@ impl Document {
                 Colour::Blue.paint(self.id()),
                 -self.version()
             ));
+            match self.state() {
+                Some(DraftState::Expired) => {
+                    output.push_str(&format!("{} ", Colour::Red.paint("[expired]")))
+                }
+                Some(DraftState::Replaced(id)) => output.push_str(&format!(
+                    "{} ",
+                    Colour::Yellow.paint(format!("[replaced by {}]", fmt_id(&id)))
+                )),
+                _ => {}
+            }
         } else {
             output.push_str(&format!(
                 "{} ",
                 Colour::Cyan.bold().paint(self.id().to_uppercase())
             ));
+            if let Some(series) = self.meta()?.fmt_series() {
+                output.push_str(&format!("({}) ", Colour::Cyan.paint(series)));
+            }
+        }
+        if let Some(annotation) = &self.annotation {
+            if annotation.starred {
+                output.push_str(&format!("{} ", Colour::Yellow.paint("*")));
+            }
+            if !annotation.tags.is_empty() {
+                output.push_str(&format!("{} ", Colour::Green.paint(annotation.fmt_tags())));
+            }
         }
         output.push_str(&format!(
             "{}",
@@ -115,70 +429,170 @@ impl Document {
             ));
         } else {
             output.push_str(&format!(
-                "{}\n\n",
+                "{}",
                 Colour::Cyan.bold().paint(self.id().to_uppercase())
             ));
+            if let Some(series) = self.meta()?.fmt_series() {
+                output.push_str(&format!(" ({})", Colour::Cyan.paint(series)));
+            }
+            output.push_str("\n\n");
+        }
+        if let Some(status) = self.meta()?.fmt_status() {
+            output.push_str(&format!("{}\n\n", Colour::Red.bold().paint(status)));
         }
         output.push_str(&format!(
             "{}",
             Colour::White.italic().paint(self.meta()?.fmt_summary())
         ));
+        if let Some(annotation) = &self.annotation {
+            output.push_str(&format!(
+                "\n\n{}",
+                Colour::Green.paint(annotation.fmt_summary())
+            ));
+        }
         Ok(output)
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Metadata(HashMap<String, MetadataAttr>);
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Policy {
+    #[default]
+    Strict,
+    KeepFirst,
+    KeepLast,
+    Multivalued,
+}
+
+pub const POLICIES: &[&str] = &["strict", "first", "last", "multi"];
+
+impl FromStr for Policy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "strict" => Ok(Policy::Strict),
+            "first" => Ok(Policy::KeepFirst),
+            "last" => Ok(Policy::KeepLast),
+            "multi" => Ok(Policy::Multivalued),
+            _ => Err(Error::CliError(format!("Unknown parsing policy '{}'", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    attrs: HashMap<String, MetadataAttr>,
+    policy: Policy,
+    warnings: Vec<String>,
+}
 
 impl Metadata {
-    fn from_html(html: kuchiki::NodeRef) -> Result<Metadata> {
-        let mut meta = HashMap::new();
-        for node in html.select(SELECTOR)? {
-            let attrs = node.attributes.borrow();
-            let key = match attrs.get("name") {
-                Some(key) if key.starts_with(PREFIX) => {
-                    key.strip_prefix(PREFIX).unwrap().to_string()
+    pub fn new() -> Self {
+        Metadata::default()
+    }
+
+    pub fn with_policy(policy: Policy) -> Self {
+        Metadata {
+            policy,
+            ..Metadata::default()
+        }
+    }
+
+    pub fn insert(&mut self, key: String, value: String) -> Result<()> {
+        let multivalued = MULTIVALUED.contains(&(key.as_str()));
+        let policy = self.policy;
+        let mut e = match self.attrs.entry(key) {
+            Entry::Vacant(e) => {
+                if multivalued {
+                    e.insert(MetadataAttr::Many(Vec::from([value])));
+                } else {
+                    e.insert(MetadataAttr::One(value));
                 }
-                Some(_) | None => continue,
-            };
-            let multivalued = MULTIVALUED.contains(&(key.as_str()));
-            let value = match attrs.get("content") {
-                Some(value) => value.to_string(),
-                None => continue,
-            };
-            match meta.entry(key) {
-                Entry::Vacant(e) => {
-                    if multivalued {
-                        e.insert(MetadataAttr::Many(Vec::from([value])));
-                    } else {
-                        e.insert(MetadataAttr::One(value));
-                    }
+                return Ok(());
+            }
+            Entry::Occupied(e) => e,
+        };
+        let warning = match (e.get_mut(), multivalued) {
+            (MetadataAttr::Many(values), _) => {
+                values.push(value);
+                return Ok(());
+            }
+            (MetadataAttr::One(_), true) => {
+                if policy == Policy::Strict {
+                    return Err(Error::AttributeTypeMismatch(format!(
+                        "Expected multivalued attribute type for '{}'",
+                        e.key()
+                    )));
                 }
-                Entry::Occupied(mut e) => {
-                    if multivalued {
-                        match e.get_mut() {
-                            MetadataAttr::One(_) => {
-                                return Err(Error::AttributeTypeMismatch(format!(
-                                    "Expected multivalued attribute type for '{}'",
-                                    e.key()
-                                )))
-                            }
-                            MetadataAttr::Many(values) => values.push(value),
+                format!("Expected multivalued attribute type for '{}'", e.key())
+            }
+            (MetadataAttr::One(_), false) => {
+                if policy == Policy::Strict {
+                    return Err(Error::DuplicateAttribute(format!(
+                        "Got unexpected duplicate attribute '{}'",
+                        e.key()
+                    )));
+                }
+                format!("Got unexpected duplicate attribute '{}'", e.key())
+            }
+        };
+        let attr = e.get_mut();
+        let action = match (policy, attr) {
+            (Policy::KeepLast, attr) => {
+                *attr = MetadataAttr::One(value);
+                "keeping last value"
+            }
+            (Policy::Multivalued, attr) => {
+                if let MetadataAttr::One(first) = attr {
+                    *attr = MetadataAttr::Many(vec![first.to_owned(), value]);
+                }
+                "promoting to multivalued"
+            }
+            _ => "keeping first value",
+        };
+        self.warnings.push(format!("{}: {}", warning, action));
+        Ok(())
+    }
+
+    pub fn warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MetadataAttr> {
+        self.attrs.get(key)
+    }
+
+    pub fn keys(&self) -> Vec<&String> {
+        self.attrs.keys().collect()
+    }
+
+    pub fn values(&self, key: &str) -> Vec<&String> {
+        match self.get(key) {
+            Some(MetadataAttr::One(value)) => vec![value],
+            Some(MetadataAttr::Many(values)) => values.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn merge(&mut self, other: &Metadata) {
+        for (key, value) in &other.attrs {
+            match (self.attrs.get_mut(key), value) {
+                (Some(MetadataAttr::Many(values)), MetadataAttr::Many(others)) => {
+                    for other in others {
+                        if !values.contains(other) {
+                            values.push(other.to_owned());
                         }
-                    } else {
-                        return Err(Error::DuplicateAttribute(format!(
-                            "Got unexpected duplicate attribute '{}'",
-                            e.key()
-                        )));
                     }
                 }
+                _ => {
+                    self.attrs.insert(key.to_owned(), value.to_owned());
+                }
             }
         }
-        Ok(Metadata(meta))
     }
 
     fn fmt(&self, attr_sep: &str, keyval_sep: &str, val_sep: &str, replace_nl: bool) -> String {
-        (&self.0)
+        self.attrs
             .iter()
             .map(|(key, value)| -> String {
                 format!(
@@ -205,9 +619,51 @@ impl Metadata {
         format!("<{}>", self.fmt(" // ", ": ", "; ", true))
     }
 
-    fn fmt_summary(&self) -> String {
+    pub fn fmt_summary(&self) -> String {
         self.fmt("\n\n", ":\n", ";\n", false)
     }
+
+    fn fmt_series(&self) -> Option<String> {
+        match self.values("Relation.IsPartOf").as_slice() {
+            [] => None,
+            series => Some(
+                series
+                    .iter()
+                    .map(|id| fmt_id(id))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+        }
+    }
+
+    fn fmt_status(&self) -> Option<String> {
+        let mut lines = STATUS
+            .iter()
+            .filter_map(|(key, label)| match self.attrs.get(*key) {
+                Some(MetadataAttr::Many(ids)) => Some(format!(
+                    "{} {}",
+                    label,
+                    ids.iter()
+                        .map(|id| fmt_id(id))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+        let errata = ERRATA_COUNTS
+            .iter()
+            .filter_map(|(key, label)| self.values(key).first().map(|n| format!("{} {}", n, label)))
+            .collect::<Vec<String>>();
+        if !errata.is_empty() {
+            lines.push(format!("Errata: {}", errata.join(", ")));
+        }
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -216,6 +672,94 @@ pub enum MetadataAttr {
     Many(Vec<String>),
 }
 
+pub fn normalize_id(id: &str) -> String {
+    let id = id.trim().to_lowercase().replace(' ', "");
+    match id.find(|c: char| c.is_ascii_digit()) {
+        Some(i)
+            if id[..i].chars().all(char::is_alphabetic)
+                && id[i..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            format!("{}{}", &id[..i], id[i..].trim_start_matches('0'))
+        }
+        _ => id,
+    }
+}
+
+fn today() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => (elapsed.as_secs() / 86_400) as i64,
+        Err(_) => 0,
+    }
+}
+
+pub fn parse_draft_name(id: &str) -> Option<(Stream, Option<String>, String)> {
+    let name = id.strip_prefix("draft-")?;
+    let (prefix, rest) = name.split_once('-').unwrap_or((name, ""));
+    let stream = match prefix {
+        "ietf" => Stream::Ietf,
+        "irtf" => Stream::Irtf,
+        "iab" => return Some((Stream::Iab, None, rest.to_string())),
+        _ => return Some((Stream::Individual, None, rest.to_string())),
+    };
+    match rest.split_once('-') {
+        Some((group, short_name)) => {
+            Some((stream, Some(group.to_string()), short_name.to_string()))
+        }
+        None => Some((stream, Some(rest.to_string()), String::new())),
+    }
+}
+
+pub fn split_revision(id: &str) -> (&str, Option<i8>) {
+    match id.rsplit_once('-') {
+        Some((draft, revision))
+            if draft.starts_with("draft")
+                && revision.len() == 2
+                && revision.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            (draft, i8::from_str(revision).ok().map(|v| -v))
+        }
+        _ => (id, None),
+    }
+}
+
+fn strip_version(draft: &str) -> String {
+    match draft.rsplit_once('-') {
+        Some((name, version)) if version.chars().all(|c| c.is_ascii_digit()) => name.to_string(),
+        _ => draft.to_string(),
+    }
+}
+
+fn cited_ids(text: &str) -> Vec<String> {
+    let words = text
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>();
+    let is_num = |word: &str| word.chars().all(|c| c.is_ascii_digit());
+    let mut ids = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let id = match word.strip_prefix("RFC") {
+            Some("") => match words.get(i + 1) {
+                Some(num) if is_num(num) => normalize_id(&format!("rfc{}", num)),
+                _ => continue,
+            },
+            Some(num) if is_num(num) => normalize_id(&format!("rfc{}", num)),
+            _ if word.starts_with("draft-") && !word.ends_with('-') => strip_version(word),
+            _ => continue,
+        };
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+pub fn fmt_id(id: &str) -> String {
+    match id.find(|c: char| c.is_ascii_digit()) {
+        Some(i) => format!("{} {}", id[..i].to_uppercase(), &id[i..]),
+        None => id.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -259,6 +803,9 @@ mod test {
         let doc = Document::from_path(path).unwrap()?;
         assert_eq!("draft-ietf-sidrops-rpkimaxlen", doc.id());
         assert_eq!(&(-5), doc.version());
+        assert_eq!(Some(Stream::Ietf), doc.stream());
+        assert_eq!(Some(&"sidrops".to_string()), doc.group());
+        assert_eq!(Some(&"rpkimaxlen".to_string()), doc.short_name());
         assert_eq!(&test_path, doc.path());
         let _meta = doc.meta()?;
         let strings = &[
@@ -294,6 +841,227 @@ mod test {
     }
 
     #[test]
+    fn test_well_formed_rfc_text() -> Result<()> {
+        let file = "rfc6468.txt";
+        let path = resource_path(file);
+        let doc = Document::from_path(path).unwrap()?;
+        assert_eq!("rfc6468", doc.id());
+        assert_eq!(&0, doc.version());
+        let strings = &[
+            file,
+            "RFC6468",
+            "urn:ietf:rfc:6468",
+            "Sieve Notification Mechanism: SIP MESSAGE",
+            "A. Melnikov",
+            "B. Leiba",
+            "K. Li",
+            "February 2012",
+            "Standards Track",
+            "This document describes a profile of the Sieve extension for",
+            "notifications, to allow notifications to be sent over SIP MESSAGE.",
+        ];
+        for out in &[doc.fmt_line()?, doc.fmt_summary()?] {
+            for string in strings {
+                assert!(out.contains(string), "'{}' not found in output", string);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_well_formed_rfc_xml() -> Result<()> {
+        let file = "rfc8212.xml";
+        let path = resource_path(file);
+        let doc = Document::from_path(path).unwrap()?;
+        assert_eq!("rfc8212", doc.id());
+        assert_eq!(&0, doc.version());
+        let strings = &[
+            file,
+            "RFC8212",
+            "urn:ietf:rfc:8212",
+            "Default External BGP (EBGP) Route Propagation Behavior without Policies",
+            "Jared Mauch <jared@akamai.com>",
+            "Job Snijders <job@ntt.net>",
+            "Greg Hankins <greg.hankins@nokia.com>",
+            "Akamai Technologies",
+            "July 2017",
+            "Standards Track",
+            "IETF",
+            "draft-ietf-grow-bgp-reject",
+            "rfc4271",
+            "This document updates RFC 4271 by defining the default behavior of a",
+        ];
+        for out in &[doc.fmt_line()?, doc.fmt_summary()?] {
+            for string in strings {
+                assert!(out.contains(string), "'{}' not found in output", string);
+            }
+            assert!(!out.contains("rfc8174"));
+        }
+        assert!(doc
+            .sections()?
+            .iter()
+            .any(|section| section.fmt_toc().contains("Changes to RFC 4271")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlay() -> Result<()> {
+        let overlay = Overlay::from_dir(&resource_path(""));
+        let doc = Document::from_path(resource_path("rfc8212.xml"))
+            .unwrap()?
+            .with_overlay(&overlay);
+        let out = doc.fmt_summary()?;
+        for string in &[
+            "PROPOSED STANDARD",
+            "10.17487/RFC8212",
+            "https://www.rfc-editor.org/errata/rfc8212",
+            "grow",
+        ] {
+            assert!(out.contains(string), "'{}' not found in output", string);
+        }
+        let mut meta = Metadata::new();
+        meta.insert("Relation.ObsoletedBy".to_string(), "rfc4271".to_string())?;
+        meta.insert("Relation.UpdatedBy".to_string(), "rfc6286".to_string())?;
+        meta.insert("Relation.UpdatedBy".to_string(), "rfc8212".to_string())?;
+        assert_eq!(
+            meta.fmt_status(),
+            Some("Obsoleted by RFC 4271\nUpdated by RFC 6286, RFC 8212".to_string())
+        );
+        meta.insert("Errata.Verified".to_string(), "2".to_string())?;
+        assert!(meta.fmt_status().unwrap().ends_with("\nErrata: 2 verified"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_body_references() -> Result<()> {
+        let doc = Document::from_path(resource_path("rfc6468.html")).unwrap()?;
+        let body = doc.body()?;
+        assert!(body.starts_with("Internet Engineering Task Force (IETF)"));
+        assert!(body.contains("\n\u{c}\nRFC 6468             Sieve Notification: SIP MESSAGE"));
+        let text = Document::from_path(resource_path("rfc6468.txt")).unwrap()?;
+        assert_eq!(doc.references()?, text.references()?);
+        let references = doc.references()?;
+        for id in &["rfc5741", "rfc5435", "rfc3261", "rfc2119"] {
+            assert!(
+                references.contains(&id.to_string()),
+                "'{}' not found in references",
+                id
+            );
+        }
+        assert!(!references.contains(&"rfc6468".to_string()));
+        let doc = Document::from_path(resource_path("rfc8212.xml")).unwrap()?;
+        let body = doc.body()?;
+        assert!(body.contains("3.  Changes to RFC 4271"));
+        assert!(
+            body.contains("   Routes SHALL NOT be added to an Adj-RIB-Out associated with an EBGP")
+        );
+        assert_eq!(doc.references()?, vec!["rfc4271", "rfc2119", "rfc8174"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sections() -> Result<()> {
+        for file in &["rfc6468.html", "rfc6468.txt"] {
+            let doc = Document::from_path(resource_path(file)).unwrap()?;
+            let sections = doc.sections()?;
+            let section = Section::find(&sections, "2.6").unwrap();
+            assert_eq!(section.title, "Other Definitions");
+            assert!(!section.text().contains("[Page"));
+            let definition = Section::find(&sections, "2").unwrap();
+            assert_eq!(definition.children.len(), 7);
+            assert!(Section::find(&sections, "Authors' Addresses").is_some());
+        }
+        let doc = Document::from_path(resource_path("rfc8212.xml")).unwrap()?;
+        let sections = doc.sections()?;
+        assert_eq!(
+            Section::find(&sections, "3").unwrap().title,
+            "Changes to RFC 4271"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_draft_name() {
+        for (id, stream, group, short_name) in &[
+            (
+                "draft-ietf-grow-bgp-reject",
+                Stream::Ietf,
+                Some("grow"),
+                "bgp-reject",
+            ),
+            (
+                "draft-irtf-cfrg-argon2",
+                Stream::Irtf,
+                Some("cfrg"),
+                "argon2",
+            ),
+            (
+                "draft-iab-protocol-maintenance",
+                Stream::Iab,
+                None,
+                "protocol-maintenance",
+            ),
+            (
+                "draft-leiba-rfc2119-update",
+                Stream::Individual,
+                None,
+                "rfc2119-update",
+            ),
+        ] {
+            assert_eq!(
+                parse_draft_name(id),
+                Some((*stream, group.map(str::to_string), short_name.to_string()))
+            );
+        }
+        assert_eq!(parse_draft_name("rfc8212"), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        for (date, year, month, day) in &[
+            ("2020-11-02", 2020, Some(11), Some(2)),
+            ("February, 2012", 2012, Some(2), None),
+            ("July 2017", 2017, Some(7), None),
+            ("May 1, 2017", 2017, Some(5), Some(1)),
+        ] {
+            assert_eq!(
+                Date::parse(date),
+                Some(Date {
+                    year: *year,
+                    month: *month,
+                    day: *day
+                })
+            );
+        }
+        assert_eq!(Date::parse("soon"), None);
+        assert_eq!(Date::parse("1970-01-01").unwrap().days(), 0);
+        assert_eq!(Date::parse("2020-11-02").unwrap().days(), 18568);
+        assert_eq!(Date::parse("February 2012").unwrap().days(), 15371);
+    }
+
+    #[test]
+    fn test_draft_state() -> Result<()> {
+        let path = resource_path("draft-ietf-sidrops-rpkimaxlen-05.html");
+        let doc = Document::from_path(path).unwrap()?;
+        let issued = Date::parse("2020-11-02").unwrap().days();
+        assert_eq!(doc.state_on(issued + 30), Some(DraftState::Active));
+        assert_eq!(doc.state_on(issued + 200), Some(DraftState::Expired));
+        assert_eq!(doc.days_expired_on(issued + 30), None);
+        assert_eq!(doc.days_expired_on(issued + 200), Some(15));
+        assert!(doc.fmt_line()?.contains("[expired]"));
+        let doc = doc.with_replaced_by(Some(&"rfc9319".to_string()));
+        assert_eq!(
+            doc.state_on(issued + 30),
+            Some(DraftState::Replaced("rfc9319".to_string()))
+        );
+        assert!(doc.fmt_line()?.contains("[replaced by RFC 9319]"));
+        let doc = Document::from_path(resource_path("rfc8212.xml")).unwrap()?;
+        assert_eq!(doc.state(), None);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::redundant_pattern_matching)]
     fn test_bad_path() {
         let file = "...";
         let path = resource_path(file);
@@ -302,6 +1070,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::redundant_pattern_matching)]
     fn test_no_html_suffix() {
         let file = "not-found.xhtml";
         let path = resource_path(file);
@@ -315,8 +1084,8 @@ mod test {
         let path = resource_path(file);
         let maybe_doc = Document::from_path(path).unwrap().unwrap();
         assert!(matches!(
-            maybe_doc.ensure_meta(),
-            Err(Error::DocumentParseError(_))
+            maybe_doc.ensure_meta().unwrap_err().kind(),
+            Error::DocumentParseError(_)
         ))
     }
 
@@ -325,9 +1094,42 @@ mod test {
         let file = "draft-duplicates-00.html";
         let path = resource_path(file);
         let maybe_doc = Document::from_path(path).unwrap().unwrap();
-        assert!(matches!(
-            maybe_doc.ensure_meta(),
-            Err(Error::DuplicateAttribute(_))
-        ))
+        let err = maybe_doc.ensure_meta().unwrap_err();
+        assert!(matches!(err.kind(), Error::DuplicateAttribute(_)));
+        assert!(err.to_string().contains(file));
+    }
+
+    #[test]
+    fn test_lenient_policies() -> Result<()> {
+        let path = resource_path("draft-duplicates-00.html");
+        for (policy, expected) in &[
+            (
+                Policy::KeepFirst,
+                vec!["urn:ietf:id:ietf-sidrops-rpkimaxlen"],
+            ),
+            (Policy::KeepLast, vec!["urn:ietf:id:ietf-sidrops-DUPLICATE"]),
+            (
+                Policy::Multivalued,
+                vec![
+                    "urn:ietf:id:ietf-sidrops-rpkimaxlen",
+                    "urn:ietf:id:ietf-sidrops-DUPLICATE",
+                ],
+            ),
+        ] {
+            let doc = Document::from_path(path.to_owned())
+                .unwrap()?
+                .with_policy(*policy);
+            let meta = doc.meta()?;
+            assert_eq!(&meta.values("Identifier"), expected);
+            assert_eq!(meta.warnings().len(), 1);
+            assert!(meta.warnings()[0].contains("'Identifier'"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(Policy::from_str("multi").unwrap(), Policy::Multivalued);
+        assert!(matches!(Policy::from_str("lax"), Err(Error::CliError(_))));
     }
 }
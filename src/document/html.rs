@@ -0,0 +1,60 @@
+use kuchiki::traits::*;
+
+use crate::document::{Backend, Document, Metadata};
+use crate::errors::{Error, Result};
+
+const SELECTOR: &str = "head>meta";
+
+const PREFIX: &str = "DC.";
+
+const BODY_SELECTOR: &str = "pre";
+
+#[derive(Debug)]
+pub struct Html;
+
+impl Backend for Html {
+    fn suffix(&self) -> &'static str {
+        ".html"
+    }
+
+    fn parse(&self, doc: &Document) -> Result<Metadata> {
+        let html = kuchiki::parse_html()
+            .from_utf8()
+            .from_file(doc.path())
+            .map_err(Error::DocumentParseError)?;
+        let mut meta = Metadata::with_policy(doc.policy());
+        for node in html.select(SELECTOR)? {
+            let attrs = node.attributes.borrow();
+            let key = match attrs.get("name") {
+                Some(key) if key.starts_with(PREFIX) => {
+                    key.strip_prefix(PREFIX).unwrap().to_string()
+                }
+                Some(_) | None => continue,
+            };
+            let value = match attrs.get("content") {
+                Some(value) => value.to_string(),
+                None => continue,
+            };
+            meta.insert(key, value)?;
+        }
+        Ok(meta)
+    }
+
+    fn body(&self, doc: &Document) -> Result<String> {
+        let html = kuchiki::parse_html()
+            .from_utf8()
+            .from_file(doc.path())
+            .map_err(Error::DocumentParseError)?;
+        let pages = html
+            .select(BODY_SELECTOR)?
+            .map(|node| {
+                let text = node.text_contents().replace('\u{a0}', " ");
+                match text.strip_prefix(" \n") {
+                    Some(page) => page.to_string(),
+                    None => text,
+                }
+            })
+            .collect::<Vec<String>>();
+        Ok(pages.join("\n\u{c}\n"))
+    }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+
+use crate::document::{Backend, Document, Metadata, MONTHS};
+use crate::errors::{Error, Result};
+
+#[derive(Debug)]
+pub struct Text;
+
+impl Backend for Text {
+    fn suffix(&self) -> &'static str {
+        ".txt"
+    }
+
+    fn parse(&self, doc: &Document) -> Result<Metadata> {
+        let text = fs::read_to_string(doc.path()).map_err(Error::DocumentParseError)?;
+        FrontMatter::from_text(&text)?.into_meta(doc)
+    }
+
+    fn body(&self, doc: &Document) -> Result<String> {
+        fs::read_to_string(doc.path()).map_err(Error::DocumentParseError)
+    }
+}
+
+#[derive(Debug, Default)]
+struct FrontMatter {
+    title: String,
+    creators: Vec<String>,
+    date: Option<String>,
+    category: Option<String>,
+    obsoletes: Vec<String>,
+    updates: Vec<String>,
+    abstract_: Option<String>,
+}
+
+impl FrontMatter {
+    fn from_text(text: &str) -> Result<Self> {
+        let mut front = FrontMatter::default();
+        let mut lines = text
+            .lines()
+            .map(|line| line.trim_end())
+            .skip_while(|line| line.is_empty());
+        let mut relation: Option<&str> = None;
+        for line in lines.by_ref().take_while(|line| !line.is_empty()) {
+            let (left, right) = split_columns(line);
+            match (left, relation) {
+                (Some(left), _) if left.contains(':') => {
+                    let (key, value) = left.split_once(':').unwrap();
+                    front.header_field(key, value);
+                    relation = Some(key);
+                }
+                (_, Some(key)) if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) => {
+                    front.header_field(key, line);
+                    continue;
+                }
+                _ => relation = None,
+            }
+            if let Some(right) = right {
+                if is_date(right) {
+                    front.date = Some(right.to_string());
+                } else if is_author(right) {
+                    front.creators.push(right.to_string());
+                }
+            }
+        }
+        front.title = lines
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.trim())
+            .take_while(|line| !line.starts_with("draft-") && !line.starts_with("<draft-"))
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if front.title.is_empty() {
+            return Err(Error::MetadataNotFound(
+                "No RFC front matter found in document".to_string(),
+            ));
+        }
+        front.abstract_ = section_text(text, "Abstract");
+        Ok(front)
+    }
+
+    fn header_field(&mut self, key: &str, value: &str) {
+        match key.trim() {
+            "Category" | "Intended status" => self.category = Some(value.trim().to_string()),
+            "Obsoletes" => self.obsoletes.extend(rfc_list(value)),
+            "Updates" => self.updates.extend(rfc_list(value)),
+            _ => {}
+        }
+    }
+
+    fn into_meta(self, doc: &Document) -> Result<Metadata> {
+        let mut meta = Metadata::with_policy(doc.policy());
+        meta.insert("Identifier".to_string(), doc.urn())?;
+        meta.insert("Title".to_string(), self.title)?;
+        for creator in self.creators {
+            meta.insert("Creator".to_string(), creator)?;
+        }
+        if let Some(date) = self.date {
+            meta.insert("Date.Issued".to_string(), date)?;
+        }
+        if let Some(category) = self.category {
+            meta.insert("Category".to_string(), category)?;
+        }
+        for rfc in self.obsoletes {
+            meta.insert("Relation.Obsoletes".to_string(), rfc)?;
+        }
+        for rfc in self.updates {
+            meta.insert("Relation.Updates".to_string(), rfc)?;
+        }
+        if let Some(abstract_) = self.abstract_ {
+            meta.insert("Description.Abstract".to_string(), abstract_)?;
+        }
+        Ok(meta)
+    }
+}
+
+fn split_columns(line: &str) -> (Option<&str>, Option<&str>) {
+    if line.starts_with(' ') {
+        return (None, Some(line.trim()));
+    }
+    match line.find("  ") {
+        Some(i) => (Some(&line[..i]), Some(line[i..].trim())),
+        None => (Some(line), None),
+    }
+}
+
+fn is_date(value: &str) -> bool {
+    MONTHS.iter().any(|month| value.starts_with(month))
+        && value.ends_with(|c: char| c.is_ascii_digit())
+}
+
+fn is_author(value: &str) -> bool {
+    let mut words = value.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some(initials), Some(_)) => {
+            initials.len() <= 6
+                && initials.ends_with('.')
+                && initials.starts_with(|c: char| c.is_uppercase())
+        }
+        _ => false,
+    }
+}
+
+fn rfc_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .filter_map(|item| item.split_whitespace().next())
+        .filter(|num| num.chars().all(|c| c.is_ascii_digit()))
+        .map(|num| format!("rfc{}", num))
+        .collect()
+}
+
+fn section_text(text: &str, heading: &str) -> Option<String> {
+    let paragraph = text
+        .lines()
+        .skip_while(|line| line.trim_end() != heading)
+        .skip(1)
+        .skip_while(|line| line.trim().is_empty())
+        .take_while(|line| line.is_empty() || line.starts_with(' '))
+        .map(|line| line.trim())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    match paragraph.trim_end() {
+        "" => None,
+        paragraph => Some(paragraph.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_front_matter_relations() -> Result<()> {
+        let text = "\
+Internet Engineering Task Force (IETF)                         P. Resnick
+Request for Comments: 5322                         Qualcomm Incorporated
+Obsoletes: 2822                                             October 2008
+Updates: 4021, 1234,
+         5678 (if approved)
+Category: Standards Track
+
+
+                            Internet Message Format
+
+Abstract
+
+   This document specifies the Internet Message Format (IMF).
+
+Status of This Memo
+";
+        let front = FrontMatter::from_text(text)?;
+        assert_eq!(front.title, "Internet Message Format");
+        assert_eq!(front.creators, vec!["P. Resnick"]);
+        assert_eq!(front.date, Some("October 2008".to_string()));
+        assert_eq!(front.category, Some("Standards Track".to_string()));
+        assert_eq!(front.obsoletes, vec!["rfc2822"]);
+        assert_eq!(front.updates, vec!["rfc4021", "rfc1234", "rfc5678"]);
+        assert_eq!(
+            front.abstract_,
+            Some("This document specifies the Internet Message Format (IMF).".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        assert!(matches!(
+            FrontMatter::from_text(""),
+            Err(Error::MetadataNotFound(_))
+        ))
+    }
+}
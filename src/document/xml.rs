@@ -0,0 +1,288 @@
+use std::fs;
+
+use roxmltree::{Node, ParsingOptions};
+
+use crate::document::{strip_version, Backend, Document, Metadata, MONTHS};
+use crate::errors::{Error, Result};
+
+const CATEGORIES: &[(&str, &str)] = &[
+    ("std", "Standards Track"),
+    ("bcp", "Best Current Practice"),
+    ("info", "Informational"),
+    ("exp", "Experimental"),
+    ("historic", "Historic"),
+];
+
+const SERIES: &[&str] = &["BCP", "STD", "FYI"];
+
+const WIDTH: usize = 72;
+
+const INDENT: usize = 3;
+
+#[derive(Debug)]
+pub struct Xml;
+
+impl Backend for Xml {
+    fn suffix(&self) -> &'static str {
+        ".xml"
+    }
+
+    fn parse(&self, doc: &Document) -> Result<Metadata> {
+        let text = fs::read_to_string(doc.path()).map_err(Error::DocumentParseError)?;
+        let xml = parse_xml(&text)?;
+        let rfc = xml.root_element();
+        let front = match child(rfc, "front") {
+            Some(front) if rfc.has_tag_name("rfc") => front,
+            _ => {
+                return Err(Error::MetadataNotFound(
+                    "No <rfc/> <front/> element found in document".to_string(),
+                ))
+            }
+        };
+        let mut meta = Metadata::with_policy(doc.policy());
+        meta.insert("Identifier".to_string(), doc.urn())?;
+        if let Some(title) = child(front, "title") {
+            meta.insert("Title".to_string(), text_of(title))?;
+        }
+        for author in children(front, "author") {
+            if let Some(name) = author.attribute("fullname") {
+                let creator = match child(author, "address").and_then(|a| child(a, "email")) {
+                    Some(email) => format!("{} <{}>", name, text_of(email)),
+                    None => name.to_string(),
+                };
+                meta.insert("Creator".to_string(), creator)?;
+            }
+            if let Some(org) = child(author, "organization") {
+                meta.insert("Creator.Organization".to_string(), text_of(org))?;
+            }
+        }
+        if let Some(date) = child(front, "date").and_then(fmt_date) {
+            meta.insert("Date.Issued".to_string(), date)?;
+        }
+        if let Some(category) = rfc.attribute("category") {
+            let category = CATEGORIES
+                .iter()
+                .find(|(key, _)| key == &category)
+                .map_or(category, |(_, name)| name);
+            meta.insert("Category".to_string(), category.to_string())?;
+        }
+        let stream = children(front, "seriesInfo")
+            .find_map(|info| info.attribute("stream"))
+            .or_else(|| rfc.attribute("submissionType"));
+        if let Some(stream) = stream {
+            meta.insert("Stream".to_string(), stream.to_string())?;
+        }
+        if let Some(consensus) = rfc.attribute("consensus") {
+            meta.insert("Consensus".to_string(), consensus.to_string())?;
+        }
+        for keyword in children(front, "keyword") {
+            meta.insert("Subject".to_string(), text_of(keyword))?;
+        }
+        for (attr, key) in &[
+            ("obsoletes", "Relation.Obsoletes"),
+            ("updates", "Relation.Updates"),
+        ] {
+            for num in rfc.attribute(*attr).unwrap_or("").split(',') {
+                let num = num.trim();
+                if !num.is_empty() {
+                    meta.insert(key.to_string(), format!("rfc{}", num))?;
+                }
+            }
+        }
+        if let (Some(_), Some(draft)) = (rfc.attribute("number"), rfc.attribute("docName")) {
+            meta.insert("Relation.Replaces".to_string(), strip_version(draft))?;
+        }
+        for info in children(front, "seriesInfo") {
+            match (info.attribute("name"), info.attribute("value")) {
+                (Some(name), Some(value)) if SERIES.contains(&name) => meta.insert(
+                    "Relation.IsPartOf".to_string(),
+                    format!("{}{}", name.to_lowercase(), value),
+                )?,
+                _ => {}
+            }
+        }
+        if let Some(abstract_) = child(front, "abstract") {
+            let paragraphs = abstract_
+                .children()
+                .filter(|n| n.is_element())
+                .map(text_of)
+                .collect::<Vec<String>>();
+            meta.insert("Description.Abstract".to_string(), paragraphs.join("\n\n"))?;
+        }
+        Ok(meta)
+    }
+
+    fn body(&self, doc: &Document) -> Result<String> {
+        let text = fs::read_to_string(doc.path()).map_err(Error::DocumentParseError)?;
+        let xml = parse_xml(&text)?;
+        let rfc = xml.root_element();
+        let mut lines = Vec::new();
+        if let Some(front) = child(rfc, "front") {
+            if let Some(title) = child(front, "title") {
+                let title = text_of(title);
+                let pad = WIDTH.saturating_sub(title.len()) / 2;
+                lines.push(format!("{}{}", " ".repeat(pad), title));
+                lines.push(String::new());
+            }
+            if let Some(abstract_) = child(front, "abstract") {
+                lines.push("Abstract".to_string());
+                lines.push(String::new());
+                render_blocks(abstract_, INDENT, &mut lines);
+            }
+        }
+        for part in &["middle", "back"] {
+            if let Some(part) = child(rfc, part) {
+                render_sections(part, &mut lines);
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+fn parse_xml(text: &str) -> Result<roxmltree::Document<'_>> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    match roxmltree::Document::parse_with_options(text, options) {
+        Ok(xml) => Ok(xml),
+        Err(e) => Err(Error::XmlParseError(format!("Failed to parse XML: {}", e))),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+fn text_of(node: Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn fmt_date(date: Node) -> Option<String> {
+    let year = date.attribute("year")?;
+    let month = match date.attribute("month") {
+        Some(month) => match month.parse::<usize>() {
+            Ok(n) if (1..=12).contains(&n) => MONTHS[n - 1],
+            _ => month,
+        },
+        None => return Some(year.to_string()),
+    };
+    match date.attribute("day") {
+        Some(day) => Some(format!("{} {}, {}", month, day, year)),
+        None => Some(format!("{} {}", month, year)),
+    }
+}
+
+fn render_sections(node: Node, lines: &mut Vec<String>) {
+    for section in node
+        .children()
+        .filter(|n| n.has_tag_name("section") || n.has_tag_name("references"))
+    {
+        let num = section
+            .attribute("pn")
+            .and_then(|pn| pn.strip_prefix("section-"))
+            .unwrap_or("");
+        let name = child(section, "name").map(text_of).unwrap_or_default();
+        match num.strip_prefix("appendix.") {
+            Some(letter) => lines.push(format!("Appendix {}.  {}", letter.to_uppercase(), name)),
+            None if num.is_empty() => lines.push(name),
+            None if num.starts_with(|c: char| c.is_ascii_lowercase()) => {
+                lines.push(format!("{}.  {}", num.to_uppercase(), name))
+            }
+            None => lines.push(format!("{}.  {}", num, name)),
+        }
+        lines.push(String::new());
+        render_blocks(section, INDENT, lines);
+        render_sections(section, lines);
+    }
+}
+
+fn render_blocks(node: Node, indent: usize, lines: &mut Vec<String>) {
+    for block in node.children().filter(|n| n.is_element()) {
+        match block.tag_name().name() {
+            "t" => wrap(&text_of(block), indent, "", lines),
+            "artwork" | "sourcecode" => {
+                let text = block.text().unwrap_or("");
+                for line in text.trim_matches('\n').lines() {
+                    lines.push(format!("{}{}", " ".repeat(indent), line));
+                }
+                lines.push(String::new());
+            }
+            "ul" | "ol" => {
+                for (i, item) in children(block, "li").enumerate() {
+                    let bullet = match block.tag_name().name() {
+                        "ol" => format!("{}.  ", i + 1),
+                        _ => "*  ".to_string(),
+                    };
+                    wrap(&text_of(item), indent, &bullet, lines);
+                }
+            }
+            "dl" => {
+                for item in block.children().filter(|n| n.is_element()) {
+                    match item.tag_name().name() {
+                        "dt" => lines.push(format!("{}{}", " ".repeat(indent), text_of(item))),
+                        _ => wrap(&text_of(item), indent + INDENT, "", lines),
+                    }
+                }
+            }
+            "figure" => render_blocks(block, indent, lines),
+            "blockquote" | "aside" | "note" => render_blocks(block, indent + INDENT, lines),
+            "reference" => {
+                let anchor = block.attribute("anchor").unwrap_or("");
+                let mut parts = child(block, "front")
+                    .and_then(|front| child(front, "title"))
+                    .map(|title| vec![format!("\"{}\"", text_of(title))])
+                    .unwrap_or_default();
+                // The series keep the cited ids in the body, e.g. "RFC 2119".
+                for info in children(block, "seriesInfo") {
+                    if let (Some(name), Some(value)) =
+                        (info.attribute("name"), info.attribute("value"))
+                    {
+                        parts.push(format!("{} {}", name, value));
+                    }
+                }
+                wrap(
+                    &format!("{}.", parts.join(", ")),
+                    indent,
+                    &format!("[{}]  ", anchor),
+                    lines,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn wrap(text: &str, indent: usize, prefix: &str, lines: &mut Vec<String>) {
+    let mut line = format!("{}{}", " ".repeat(indent), prefix);
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.len() + 1 + word.len() > WIDTH {
+            lines.push(line);
+            line = " ".repeat(indent + prefix.len());
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    if !empty {
+        lines.push(line);
+    }
+    lines.push(String::new());
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::document::{fmt_id, normalize_id};
+use crate::errors::{Error, Result};
+
+pub const ERRATA: &str = "errata.json";
+
+pub const STATUSES: &[&str] = &["verified", "reported", "held", "rejected"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Erratum {
+    pub id: String,
+    pub doc_id: String,
+    pub status: String,
+    pub kind: String,
+    pub section: String,
+    pub submitted: String,
+    pub submitter: String,
+    pub original: String,
+    pub corrected: String,
+    pub notes: String,
+}
+
+impl Erratum {
+    fn parse(value: &Value) -> Option<Self> {
+        let field = |key: &str| match value.get(key) {
+            Some(Value::String(s)) => s.trim().to_string(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => String::new(),
+        };
+        let doc_id = field("doc-id");
+        if doc_id.is_empty() {
+            return None;
+        }
+        Some(Erratum {
+            id: field("errata_id"),
+            doc_id: normalize_id(&doc_id),
+            status: field("errata_status_code"),
+            kind: field("errata_type_code"),
+            section: field("section"),
+            submitted: field("submit_date"),
+            submitter: field("submitter_name"),
+            original: field("orig_text"),
+            corrected: field("correct_text"),
+            notes: field("notes"),
+        })
+    }
+
+    pub fn status_key(&self) -> &'static str {
+        let status = self.status.to_ascii_lowercase();
+        STATUSES
+            .iter()
+            .find(|key| status.starts_with(*key))
+            .copied()
+            .unwrap_or("")
+    }
+
+    pub fn fmt_text(&self) -> String {
+        let mut heading = format!("Erratum {} ({}", self.id, self.status);
+        if !self.kind.is_empty() {
+            heading.push_str(&format!(", {}", self.kind));
+        }
+        heading.push(')');
+        match self.section.as_str() {
+            "" => {}
+            section if section.eq_ignore_ascii_case("GLOBAL") => heading.push_str(", throughout"),
+            section => heading.push_str(&format!(", Section {}", section)),
+        }
+        if !self.submitter.is_empty() {
+            heading.push_str(&format!(", reported by {}", self.submitter));
+        }
+        if !self.submitted.is_empty() {
+            heading.push_str(&format!(" on {}", self.submitted));
+        }
+        let mut parts = vec![heading];
+        for (title, text) in &[
+            ("Original Text", &self.original),
+            ("Corrected Text", &self.corrected),
+            ("Notes", &self.notes),
+        ] {
+            if !text.is_empty() {
+                parts.push(format!("{}\n{}\n{}", title, "-".repeat(title.len()), text));
+            }
+        }
+        parts.join("\n\n")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Errata(HashMap<String, Vec<Erratum>>);
+
+impl Errata {
+    pub fn from_dir(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(ERRATA);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Errata::from_path(&path).map(Some)
+    }
+
+    fn from_path(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return Err(Error::FileReadError(e).in_document(path)),
+        };
+        let entries = match serde_json::from_str::<Value>(&text) {
+            Ok(Value::Array(entries)) => entries,
+            Ok(_) => {
+                return Err(Error::ErrataParseError(
+                    "Failed to parse errata: expected a list of errata".to_string(),
+                )
+                .in_document(path))
+            }
+            Err(e) => {
+                return Err(
+                    Error::ErrataParseError(format!("Failed to parse errata: {}", e))
+                        .in_document(path),
+                )
+            }
+        };
+        let mut errata = Errata::default();
+        for erratum in entries.iter().filter_map(Erratum::parse) {
+            errata
+                .0
+                .entry(erratum.doc_id.to_owned())
+                .or_default()
+                .push(erratum);
+        }
+        Ok(errata)
+    }
+
+    pub fn get(&self, id: &str) -> &[Erratum] {
+        self.0.get(id).map_or(&[], |errata| errata.as_slice())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Vec<Erratum>)> {
+        self.0.iter()
+    }
+}
+
+pub fn count(errata: &[Erratum], status: &str) -> usize {
+    errata
+        .iter()
+        .filter(|erratum| erratum.status_key() == status)
+        .count()
+}
+
+pub fn fmt_counts(id: &str, errata: &[Erratum]) -> String {
+    let counts = STATUSES
+        .iter()
+        .map(|status| (count(errata, status), status))
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, status)| format!("{} {}", n, status))
+        .collect::<Vec<String>>();
+    match counts.as_slice() {
+        [] => format!("No errata for {}", fmt_id(id)),
+        _ => format!("{}: {}", fmt_id(id), counts.join(", ")),
+    }
+}
+
+pub fn fetch(mut proc: Command, remote: &str, path: &Path) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    proc.arg("--fail")
+        .arg("--location")
+        .arg("--output")
+        .arg(&tmp)
+        .arg(remote);
+    let status = match proc.status() {
+        Ok(status) => status,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(Error::SyncError(io::Error::new(
+                e.kind(),
+                "'curl' is required to fetch errata, but was not found",
+            )))
+        }
+        Err(e) => return Err(Error::SyncError(e)),
+    };
+    if !status.success() {
+        fs::remove_file(&tmp).ok();
+        return Err(Error::SyncError(io::Error::other(format!(
+            "failed to fetch errata from '{}' ({})",
+            remote, status
+        ))));
+    }
+    if let Err(e) = Errata::from_path(&tmp) {
+        fs::remove_file(&tmp).ok();
+        return Err(Error::SyncError(io::Error::other(format!(
+            "fetched invalid errata from '{}': {}",
+            remote, e
+        ))));
+    }
+    fs::rename(&tmp, path).map_err(Error::SyncError)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::{resource_path, scratch_dir};
+
+    #[test]
+    fn test_errata() -> Result<()> {
+        let errata = Errata::from_dir(&resource_path(""))?.unwrap();
+        let rfc6468 = errata.get("rfc6468");
+        assert_eq!(rfc6468.len(), 3);
+        assert_eq!(count(rfc6468, "verified"), 1);
+        assert_eq!(count(rfc6468, "reported"), 1);
+        assert_eq!(rfc6468[2].id, "9003");
+        assert_eq!(errata.get("rfc8174")[0].status_key(), "held");
+        assert!(errata.get("rfc8212").is_empty());
+        assert_eq!(
+            fmt_counts("rfc6468", rfc6468),
+            "RFC 6468: 1 verified, 1 reported, 1 rejected"
+        );
+        assert_eq!(fmt_counts("rfc8212", &[]), "No errata for RFC 8212");
+        let text = rfc6468[0].fmt_text();
+        assert!(text.starts_with(
+            "Erratum 9001 (Verified, Technical), Section 3, reported by Alice Example on 2012-03-01\n\n"
+        ));
+        assert!(text.contains("Corrected Text\n--------------\nThe \"from\" tag MUST NOT"));
+        assert!(!rfc6468[1].fmt_text().contains("Notes"));
+        assert!(rfc6468[1].fmt_text().contains(", throughout,"));
+        assert!(Errata::from_dir(&resource_path("missing"))?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch() -> Result<()> {
+        let dir = scratch_dir("errata-fetch");
+        let remote = format!("file://{}", resource_path(ERRATA).to_string_lossy());
+        let mut proc = Command::new("/bin/sh");
+        proc.arg("-c").arg("cp \"${5#file://}\" \"$4\"").arg("curl");
+        fetch(proc, &remote, &dir.join(ERRATA))?;
+        assert!(Errata::from_dir(&dir)?.is_some());
+        let mut proc = Command::new("/bin/sh");
+        proc.arg("-c").arg("exit 22").arg("curl");
+        assert!(matches!(
+            fetch(proc, &remote, &dir.join(ERRATA)),
+            Err(Error::SyncError(_))
+        ));
+        assert!(!dir.join("errata.json.tmp").exists());
+        let mut proc = Command::new("/bin/sh");
+        proc.arg("-c")
+            .arg("echo '<html>503</html>' > \"$4\"")
+            .arg("curl");
+        assert!(matches!(
+            fetch(proc, &remote, &dir.join(ERRATA)),
+            Err(Error::SyncError(_))
+        ));
+        assert!(!dir.join("errata.json.tmp").exists());
+        assert!(Errata::from_dir(&dir)?.is_some());
+        match fetch(Command::new(dir.join("curl")), &remote, &dir.join(ERRATA)) {
+            Err(Error::SyncError(e)) => assert!(e.to_string().contains("'curl' is required")),
+            _ => panic!("expected a missing curl to be reported"),
+        }
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
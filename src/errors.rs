@@ -1,24 +1,124 @@
 use std::convert::From;
+use std::error;
+use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
+
+// Process exit codes, as documented in the README.
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_PARSE: i32 = 4;
+pub const EXIT_SYNC: i32 = 5;
+pub const EXIT_PARTIAL: i32 = 6;
 
 #[derive(Debug)]
 pub enum Error {
+    AnnotationError(String),
     AttributeTypeMismatch(String),
     CliError(String),
     DirectoryReadError(io::Error),
+    DocumentError(PathBuf, Box<Error>),
     DocumentNotFound(String),
     DocumentParseError(io::Error),
     DuplicateAttribute(String),
+    ErrataParseError(String),
+    ExtractError(io::Error),
+    FileReadError(io::Error),
+    HistoryError(io::Error),
     ImplementationNotFound(String),
+    IntegrityError(String),
+    LockError(String),
     MetadataNotFound(String),
     MetadataRetrieval(String),
+    PartialFailure(String),
+    PruneError(io::Error),
+    ServerError(String),
     SyncError(io::Error),
+    TerminalError(io::Error),
     UserDirectories(String),
+    XmlParseError(String),
+}
+
+impl Error {
+    pub fn in_document(self, path: &Path) -> Self {
+        match self {
+            Error::DocumentError(..) => self,
+            _ => Error::DocumentError(path.to_owned(), Box::new(self)),
+        }
+    }
+
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::DocumentError(_, err) => err.kind(),
+            _ => self,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self.kind() {
+            Error::CliError(_) | Error::ImplementationNotFound(_) => EXIT_USAGE,
+            Error::DocumentNotFound(_) => EXIT_NOT_FOUND,
+            Error::AttributeTypeMismatch(_)
+            | Error::DocumentParseError(_)
+            | Error::DuplicateAttribute(_)
+            | Error::ErrataParseError(_)
+            | Error::IntegrityError(_)
+            | Error::MetadataNotFound(_)
+            | Error::MetadataRetrieval(_)
+            | Error::XmlParseError(_) => EXIT_PARSE,
+            Error::LockError(_) | Error::SyncError(_) => EXIT_SYNC,
+            Error::PartialFailure(_) => EXIT_PARTIAL,
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DirectoryReadError(err) => write!(f, "Failed to read directory: {}", err),
+            Error::DocumentError(path, err) => write!(f, "{}: {}", path.to_string_lossy(), err),
+            Error::DocumentParseError(err) => write!(f, "Failed to read document: {}", err),
+            Error::ExtractError(err) => write!(f, "Failed to write extracted file: {}", err),
+            Error::FileReadError(err) => write!(f, "Failed to read file: {}", err),
+            Error::HistoryError(err) => write!(f, "Failed to update history: {}", err),
+            Error::PruneError(err) => write!(f, "Failed to prune: {}", err),
+            Error::SyncError(err) => write!(f, "Failed to sync: {}", err),
+            Error::TerminalError(err) => write!(f, "Terminal error: {}", err),
+            Error::AnnotationError(msg)
+            | Error::AttributeTypeMismatch(msg)
+            | Error::CliError(msg)
+            | Error::DocumentNotFound(msg)
+            | Error::DuplicateAttribute(msg)
+            | Error::ErrataParseError(msg)
+            | Error::ImplementationNotFound(msg)
+            | Error::IntegrityError(msg)
+            | Error::LockError(msg)
+            | Error::MetadataNotFound(msg)
+            | Error::MetadataRetrieval(msg)
+            | Error::PartialFailure(msg)
+            | Error::ServerError(msg)
+            | Error::UserDirectories(msg)
+            | Error::XmlParseError(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
-impl From<io::Error> for Error {
-    fn from(err: io::Error) -> Self {
-        Error::DocumentParseError(err)
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::DirectoryReadError(err)
+            | Error::DocumentParseError(err)
+            | Error::ExtractError(err)
+            | Error::FileReadError(err)
+            | Error::HistoryError(err)
+            | Error::PruneError(err)
+            | Error::SyncError(err)
+            | Error::TerminalError(err) => Some(err),
+            Error::DocumentError(_, err) => Some(err.as_ref()),
+            _ => None,
+        }
     }
 }
 
@@ -29,3 +129,48 @@ impl From<()> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_context() {
+        let err =
+            Error::DuplicateAttribute("Got unexpected duplicate attribute 'Title'".to_string())
+                .in_document(Path::new("/mirror/rfc1.html"))
+                .in_document(Path::new("/elsewhere"));
+        assert_eq!(
+            err.to_string(),
+            "/mirror/rfc1.html: Got unexpected duplicate attribute 'Title'"
+        );
+        assert!(matches!(err.kind(), Error::DuplicateAttribute(_)));
+        assert!(err.source().is_some());
+        assert_eq!(err.exit_code(), EXIT_PARSE);
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let not_found = io::Error::new(io::ErrorKind::NotFound, "rsync");
+        assert_eq!(Error::SyncError(not_found).exit_code(), EXIT_SYNC);
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "errata.json");
+        assert_eq!(
+            Error::FileReadError(denied)
+                .in_document(Path::new("/mirror/errata.json"))
+                .exit_code(),
+            EXIT_FAILURE
+        );
+        assert_eq!(Error::CliError(String::new()).exit_code(), EXIT_USAGE);
+        assert_eq!(
+            Error::DocumentNotFound(String::new()).exit_code(),
+            EXIT_NOT_FOUND
+        );
+        assert_eq!(
+            Error::PartialFailure(String::new()).exit_code(),
+            EXIT_PARTIAL
+        );
+        assert_eq!(Error::ServerError(String::new()).exit_code(), EXIT_FAILURE);
+    }
+}
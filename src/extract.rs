@@ -0,0 +1,303 @@
+use std::path::Path;
+
+use crate::section::strip_pages;
+
+pub const KINDS: &[&str] = &["abnf", "asn1", "code", "yang"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    pub name: String,
+    pub content: String,
+}
+
+pub fn extract(id: &str, body: &str, kind: &str) -> Vec<Artifact> {
+    let lines = strip_pages(body, false);
+    match kind {
+        "abnf" => abnf(id, &lines),
+        "asn1" => asn1(id, &lines),
+        "yang" => code_blocks(&lines)
+            .into_iter()
+            .filter(|(name, content)| match name {
+                Some(name) => name.ends_with(".yang"),
+                None => is_yang(content),
+            })
+            .enumerate()
+            .map(|(i, (name, content))| Artifact {
+                name: name
+                    .or_else(|| yang_module(&content).map(|module| format!("{}.yang", module)))
+                    .unwrap_or_else(|| format!("{}-yang-{}.yang", id, i + 1)),
+                content,
+            })
+            .collect(),
+        _ => code_blocks(&lines)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, content))| Artifact {
+                name: name.unwrap_or_else(|| format!("{}-code-{}.txt", id, i + 1)),
+                content,
+            })
+            .collect(),
+    }
+}
+
+fn code_blocks(lines: &[String]) -> Vec<(Option<String>, String)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+    for line in lines {
+        let trimmed = line.trim();
+        match current.as_mut() {
+            None => {
+                if let Some(rest) = trimmed.strip_prefix("<CODE BEGINS>") {
+                    current = Some((file_name(rest), Vec::new()));
+                }
+            }
+            Some((_, block)) => {
+                if trimmed.starts_with("<CODE ENDS>") {
+                    let (name, block) = current.take().unwrap();
+                    blocks.push((name, dedent(&block)));
+                } else {
+                    block.push(line);
+                }
+            }
+        }
+    }
+    blocks
+}
+
+fn file_name(marker: &str) -> Option<String> {
+    let marker = marker.trim();
+    let marker = marker.strip_prefix("file").unwrap_or(marker).trim_start();
+    let name = match marker.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => marker.split_whitespace().next(),
+    }?;
+    Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+fn is_yang(content: &str) -> bool {
+    matches!(
+        content.split_whitespace().next(),
+        Some("module") | Some("submodule")
+    )
+}
+
+// Module names become file names, so anything that could leave the output
+// directory falls back to a numbered name.
+fn yang_module(content: &str) -> Option<&str> {
+    if !is_yang(content) {
+        return None;
+    }
+    content
+        .split_whitespace()
+        .nth(1)
+        .map(|word| word.trim_end_matches('{'))
+        .filter(|word| !word.is_empty())
+        .filter(|word| word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+}
+
+fn abnf(id: &str, lines: &[String]) -> Vec<Artifact> {
+    let mut groups = Vec::new();
+    let mut group: Vec<&str> = Vec::new();
+    let mut rule_indent = None;
+    for line in lines {
+        let indent = indent(line);
+        if is_rule(line) {
+            group.push(line);
+            rule_indent = Some(indent);
+            continue;
+        }
+        if let Some(rule_indent) = rule_indent {
+            if !line.is_empty()
+                && (indent > rule_indent || (indent == rule_indent && line.trim().starts_with(';')))
+            {
+                group.push(line);
+                continue;
+            }
+        }
+        if !group.is_empty() {
+            groups.push(dedent(&group));
+            group.clear();
+        }
+        rule_indent = None;
+    }
+    if !group.is_empty() {
+        groups.push(dedent(&group));
+    }
+    if groups.is_empty() {
+        return Vec::new();
+    }
+    vec![Artifact {
+        name: format!("{}.abnf", id),
+        content: groups.join("\n"),
+    }]
+}
+
+fn is_rule(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let name_len = trimmed
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .unwrap_or(trimmed.len());
+    let rest = trimmed[name_len..].trim_start();
+    let rest = rest.strip_prefix("=/").or_else(|| rest.strip_prefix('='));
+    indent(line) > 0
+        && trimmed.starts_with(|c: char| c.is_ascii_alphabetic())
+        && rest.is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+fn asn1(id: &str, lines: &[String]) -> Vec<Artifact> {
+    let mut artifacts = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !lines[i]
+            .split_whitespace()
+            .any(|word| word == "DEFINITIONS")
+        {
+            i += 1;
+            continue;
+        }
+        let mut start = paragraph_start(lines, i);
+        if lines[start].trim_start().starts_with("DEFINITIONS") {
+            let mut previous = start;
+            while previous > 0 && lines[previous - 1].is_empty() {
+                previous -= 1;
+            }
+            if previous > 0 {
+                start = paragraph_start(lines, previous - 1);
+            }
+        }
+        let end = match lines[i..].iter().position(|line| line.trim() == "END") {
+            Some(offset) => i + offset,
+            None => break,
+        };
+        let module = lines[start]
+            .split(|c: char| c.is_whitespace() || c == '{')
+            .find(|word| !word.is_empty())
+            .filter(|word| word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        artifacts.push(Artifact {
+            name: match module {
+                Some(module) => format!("{}.asn", module),
+                None => format!("{}-asn1-{}.asn", id, artifacts.len() + 1),
+            },
+            content: dedent(
+                &lines[start..=end]
+                    .iter()
+                    .map(|line| line.as_str())
+                    .collect::<Vec<&str>>(),
+            ),
+        });
+        i = end + 1;
+    }
+    artifacts
+}
+
+fn paragraph_start(lines: &[String], mut i: usize) -> usize {
+    while i > 0 && !lines[i - 1].is_empty() {
+        i -= 1;
+    }
+    i
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+    let lines = match (start, end) {
+        (Some(start), Some(end)) => &lines[start..=end],
+        _ => return String::new(),
+    };
+    let margin = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent(line))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| format!("{}\n", line.get(margin..).unwrap_or("")))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BODY: &str = include_str!("../tests/extract/rfc9999.txt");
+
+    #[test]
+    fn test_extract_code() {
+        let artifacts = extract("rfc9999", BODY, "code");
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].name, "ietf-widget@2024-04-01.yang");
+        assert_eq!(artifacts[1].name, "rfc9999-code-2.txt");
+        assert_eq!(artifacts[1].content, "#!/bin/sh\necho widget\n");
+    }
+
+    #[test]
+    fn test_extract_yang() {
+        let artifacts = extract("rfc9999", BODY, "yang");
+        assert_eq!(artifacts.len(), 1);
+        let content = &artifacts[0].content;
+        assert!(content.starts_with("module ietf-widget {\n  yang-version 1.1;\n"));
+        assert!(content.ends_with("    }\n  }\n}\n"));
+        assert!(content.contains("      }\n      leaf colour {\n"));
+        assert!(!content.contains("[Page 1]"));
+        assert!(!content.contains("RFC 9999"));
+        let unnamed = BODY.replace(" file \"ietf-widget@2024-04-01.yang\"", "");
+        assert_eq!(
+            extract("rfc9999", &unnamed, "yang")[0].name,
+            "ietf-widget.yang"
+        );
+        let adjacent = unnamed.replace("module ietf-widget {", "module ietf-widget{");
+        assert_eq!(
+            extract("rfc9999", &adjacent, "yang")[0].name,
+            "ietf-widget.yang"
+        );
+        for module in &["/etc/widget", "../../widget"] {
+            let hostile = unnamed.replace("ietf-widget {", &format!("{} {{", module));
+            assert_eq!(
+                extract("rfc9999", &hostile, "yang")[0].name,
+                "rfc9999-yang-1.yang"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_abnf() {
+        let artifacts = extract("rfc9999", BODY, "abnf");
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].name, "rfc9999.abnf");
+        assert_eq!(
+            artifacts[0].content,
+            "\
+widget-msg   = \"WIDGET\" SP widget-name CRLF
+             *header
+widget-name  = 1*(ALPHA / DIGIT / \"-\")
+; headers are optional
+header       = field-name \":\" SP field-value CRLF
+
+field-name   = 1*ALPHA
+field-value  =/ *VCHAR
+"
+        );
+        assert!(!is_rule("   A widget-name MUST NOT be empty."));
+        assert!(!is_rule("1.  Message Syntax"));
+    }
+
+    #[test]
+    fn test_extract_asn1() {
+        let artifacts = extract("rfc9999", BODY, "asn1");
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].name, "WidgetModule.asn");
+        assert!(artifacts[0].content.starts_with("WidgetModule { iso(1)"));
+        assert!(artifacts[0]
+            .content
+            .contains("\nDEFINITIONS IMPLICIT TAGS ::=\n\nBEGIN\n"));
+        assert!(artifacts[0].content.ends_with("\nEND\n"));
+        assert!(extract("rfc9999", "no modules here", "asn1").is_empty());
+    }
+}
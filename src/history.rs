@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY: &str = "history";
+pub const NO_HISTORY: &str = "no-history";
+
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug)]
+pub struct History(PathBuf);
+
+impl History {
+    pub fn new(dir: &Path) -> Self {
+        History(dir.join(HISTORY))
+    }
+
+    pub fn record(&self, id: &str) -> io::Result<()> {
+        // A 'no-history' file next to the history turns recording off for good.
+        if self.0.with_file_name(NO_HISTORY).exists() {
+            return Ok(());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.0)?;
+        writeln!(file, "{} {}", now, id)?;
+        drop(file);
+        let lines = self.lines()?;
+        if lines.len() > MAX_ENTRIES {
+            let tmp = self.0.with_extension("tmp");
+            let content = lines[lines.len() - MAX_ENTRIES..]
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect::<String>();
+            fs::write(&tmp, content)?;
+            fs::rename(&tmp, &self.0)?;
+        }
+        Ok(())
+    }
+
+    pub fn recent(&self) -> io::Result<Vec<String>> {
+        let mut seen = HashSet::new();
+        Ok(self
+            .lines()?
+            .iter()
+            .rev()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .filter(|id| seen.insert(id.to_string()))
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.0) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn lines(&self) -> io::Result<Vec<String>> {
+        match fs::read_to_string(&self.0) {
+            Ok(content) => Ok(content.lines().map(|line| line.to_string()).collect()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::scratch_dir;
+
+    #[test]
+    fn test_history() -> io::Result<()> {
+        let dir = scratch_dir("history");
+        let history = History::new(&dir);
+        assert!(history.recent()?.is_empty());
+        for id in &["rfc6468", "rfc8174", "rfc6468", "rfc8212"] {
+            history.record(id)?;
+        }
+        assert_eq!(history.recent()?, vec!["rfc8212", "rfc6468", "rfc8174"]);
+        history.clear()?;
+        assert!(!dir.join(HISTORY).exists());
+        history.clear()?;
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_disabled() -> io::Result<()> {
+        let dir = scratch_dir("history-disabled");
+        let history = History::new(&dir);
+        fs::write(dir.join(NO_HISTORY), "")?;
+        history.record("rfc6468")?;
+        assert!(history.recent()?.is_empty());
+        assert!(!dir.join(HISTORY).exists());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_limit() -> io::Result<()> {
+        let dir = scratch_dir("history-limit");
+        let history = History::new(&dir);
+        let content = (0..MAX_ENTRIES)
+            .map(|i| format!("0 draft-{}\n", i))
+            .collect::<String>();
+        fs::write(dir.join(HISTORY), content)?;
+        history.record("rfc6468")?;
+        let recent = history.recent()?;
+        assert_eq!(recent.len(), MAX_ENTRIES);
+        assert_eq!(recent.first().unwrap(), "rfc6468");
+        assert_eq!(recent.last().unwrap(), "draft-1");
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
@@ -0,0 +1,544 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::UNIX_EPOCH;
+
+use pipeliner::Pipeline;
+use serde_json::{json, Value};
+
+use crate::collection::Collection;
+use crate::document::Document;
+use crate::section::{sentences, Section};
+
+pub const IANA_CACHE: &str = ".iana-cache";
+
+const REGISTRY_WORDS: &[&str] = &["registry", "registries", "sub-registry", "subregistry"];
+const NAMING_WORDS: &[&str] = &["called", "named", "entitled", "titled"];
+const VALUE_WORDS: &[&str] = &[
+    "value",
+    "code",
+    "codepoint",
+    "point",
+    "type",
+    "number",
+    "bit",
+];
+const FIELD_SUFFIXES: &[&str] = &[
+    "name",
+    "value",
+    "code",
+    "codepoint",
+    "type",
+    "number",
+    "keyword",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry {
+    pub name: String,
+    pub section: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Considerations {
+    pub section: String,
+    pub registries: Vec<Registry>,
+    pub no_actions: bool,
+}
+
+impl Considerations {
+    pub fn parse(sections: &[Section]) -> Option<Self> {
+        let iana = Section::flatten(sections).into_iter().find(|section| {
+            section
+                .title
+                .to_ascii_lowercase()
+                .contains("iana considerations")
+        })?;
+        let mut considerations = Considerations {
+            section: label(iana),
+            registries: Vec::new(),
+            no_actions: false,
+        };
+        for section in Section::flatten(std::slice::from_ref(iana)) {
+            let sentences = section
+                .paragraphs()
+                .iter()
+                .flat_map(|paragraph| {
+                    sentences(paragraph)
+                        .into_iter()
+                        .map(|sentence| sentence.to_string())
+                        .collect::<Vec<String>>()
+                })
+                .collect::<Vec<String>>();
+            if sentences.iter().any(|sentence| is_no_actions(sentence)) {
+                considerations.no_actions = true;
+            }
+            let mut names = sentences
+                .iter()
+                .flat_map(|sentence| registry_names(sentence))
+                .collect::<Vec<String>>();
+            let mut values = sentences
+                .iter()
+                .flat_map(|sentence| sentence_values(sentence))
+                .collect::<Vec<String>>();
+            values.extend(section.lines().iter().filter_map(|line| field_value(line)));
+            dedup(&mut names);
+            dedup(&mut values);
+            if names.is_empty() && !values.is_empty() {
+                if !std::ptr::eq(section, iana) {
+                    names.push(section.title.to_owned());
+                } else if let Some(last) = considerations.registries.last_mut() {
+                    last.values.extend(values);
+                    dedup(&mut last.values);
+                    continue;
+                }
+            }
+            for name in names {
+                match considerations
+                    .registries
+                    .iter_mut()
+                    .find(|registry| fold(&registry.name) == fold(&name))
+                {
+                    Some(registry) => {
+                        registry.values.extend(values.iter().cloned());
+                        dedup(&mut registry.values);
+                    }
+                    None => considerations.registries.push(Registry {
+                        name,
+                        section: label(section),
+                        values: values.to_owned(),
+                    }),
+                }
+            }
+        }
+        Some(considerations)
+    }
+
+    pub fn fmt_table(&self) -> String {
+        if self.registries.is_empty() {
+            return match self.no_actions {
+                true => "No IANA actions".to_string(),
+                false => "No IANA registries found".to_string(),
+            };
+        }
+        let mut lines = Vec::new();
+        for registry in &self.registries {
+            lines.push(format!("{:<10} {}", registry.section, registry.name));
+            for value in &registry.values {
+                lines.push(format!("{:<10}   {}", "", value));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "section": self.section,
+            "no_actions": self.no_actions,
+            "registries": self.registries.iter().map(|registry| json!({
+                "name": registry.name,
+                "section": registry.section,
+                "values": registry.values,
+            })).collect::<Vec<Value>>(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub docs: Vec<Document>,
+}
+
+pub struct IanaIndex(Vec<RegistryEntry>);
+
+impl IanaIndex {
+    pub fn new(collection: Collection, jobs: usize, cache: &Path) -> Self {
+        let mut cached = read_cache(cache);
+        let mut found = Vec::new();
+        let mut missed = Vec::new();
+        for doc in collection.newest(1) {
+            let key = doc.path().to_string_lossy().to_string();
+            match (mtime(&doc), cached.remove(&key)) {
+                (Some(mtime), Some((cached_mtime, names))) if mtime == cached_mtime => {
+                    found.push((doc, names))
+                }
+                _ => missed.push(doc),
+            }
+        }
+        let parsed = missed.len();
+        for (doc, result) in missed.with_threads(jobs).map(|doc| {
+            let result = doc
+                .sections()
+                .map(|sections| Considerations::parse(&sections));
+            (doc, result)
+        }) {
+            match result {
+                Ok(considerations) => {
+                    let names = considerations
+                        .map(|considerations| {
+                            considerations
+                                .registries
+                                .into_iter()
+                                .map(|registry| registry.name)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    found.push((doc, names));
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        if parsed > 0 {
+            write_cache(cache, &found);
+        }
+        let mut entries: BTreeMap<String, RegistryEntry> = BTreeMap::new();
+        for (doc, names) in found {
+            for name in names {
+                let entry = entries.entry(fold(&name)).or_insert_with(|| RegistryEntry {
+                    name,
+                    docs: Vec::new(),
+                });
+                if !entry.docs.iter().any(|other| other.id() == doc.id()) {
+                    entry.docs.push(doc.clone());
+                }
+            }
+        }
+        let mut entries = entries.into_values().collect::<Vec<RegistryEntry>>();
+        for entry in entries.iter_mut() {
+            entry.docs.sort_by(|a, b| {
+                (a.id().starts_with("draft"), a.id()).cmp(&(b.id().starts_with("draft"), b.id()))
+            });
+        }
+        IanaIndex(entries)
+    }
+
+    pub fn find(&self, query: &str) -> Vec<&RegistryEntry> {
+        let query = fold(query);
+        self.0
+            .iter()
+            .filter(|entry| fold(&entry.name).contains(&query))
+            .collect()
+    }
+
+    pub fn to_json(entries: &[&RegistryEntry]) -> Value {
+        Value::Array(
+            entries
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "registry": entry.name,
+                        "documents": entry.docs.iter().map(|doc| doc.id()).collect::<Vec<&String>>(),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a IanaIndex {
+    type Item = &'a RegistryEntry;
+    type IntoIter = std::slice::Iter<'a, RegistryEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+fn mtime(doc: &Document) -> Option<u64> {
+    fs::metadata(doc.path())
+        .and_then(|meta| meta.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|mtime| mtime.as_secs())
+}
+
+fn read_cache(path: &Path) -> HashMap<String, (u64, Vec<String>)> {
+    let value = fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+    let docs = match value {
+        Some(Value::Object(docs)) => docs,
+        _ => return HashMap::new(),
+    };
+    docs.into_iter()
+        .filter_map(|(path, entry)| {
+            let mtime = entry.get("mtime")?.as_u64()?;
+            let names = entry
+                .get("registries")?
+                .as_array()?
+                .iter()
+                .filter_map(|name| name.as_str().map(|name| name.to_string()))
+                .collect();
+            Some((path, (mtime, names)))
+        })
+        .collect()
+}
+
+fn write_cache(path: &Path, found: &[(Document, Vec<String>)]) {
+    let docs = found
+        .iter()
+        .filter_map(|(doc, names)| {
+            let entry = json!({ "mtime": mtime(doc)?, "registries": names });
+            Some((doc.path().to_string_lossy().to_string(), entry))
+        })
+        .collect::<serde_json::Map<String, Value>>();
+    let tmp = path.with_extension(process::id().to_string());
+    if fs::write(&tmp, Value::Object(docs).to_string())
+        .and_then(|_| fs::rename(&tmp, path))
+        .is_err()
+    {
+        fs::remove_file(&tmp).ok();
+    }
+}
+
+fn label(section: &Section) -> String {
+    if section.number.is_empty() {
+        section.title.to_owned()
+    } else {
+        section.number.to_owned()
+    }
+}
+
+fn fold(name: &str) -> String {
+    let name = name.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let name = name.to_ascii_lowercase();
+    REGISTRY_WORDS
+        .iter()
+        .find_map(|word| name.strip_suffix(&format!(" {}", word)))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+fn dedup(items: &mut Vec<String>) {
+    let mut seen = Vec::new();
+    items.retain(|item| {
+        let found = seen.contains(item);
+        if !found {
+            seen.push(item.to_owned());
+        }
+        !found
+    });
+}
+
+fn is_no_actions(sentence: &str) -> bool {
+    let sentence = sentence.to_ascii_lowercase();
+    let words = sentence
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .collect::<Vec<&str>>();
+    words.contains(&"iana")
+        && (words.contains(&"no") || words.contains(&"not"))
+        && ["action", "request", "consideration"]
+            .iter()
+            .any(|word| sentence.contains(word))
+        && !sentence.contains('"')
+}
+
+fn registry_names(sentence: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let pieces = sentence.split('"').collect::<Vec<&str>>();
+    for i in (1..pieces.len().saturating_sub(1)).step_by(2) {
+        let before = pieces[i - 1].to_ascii_lowercase();
+        let mut before = before.trim_end().trim_end_matches([',', ':']).trim_end();
+        if let Some(word) = NAMING_WORDS
+            .iter()
+            .find(|word| before.ends_with(&format!(" {}", word)))
+        {
+            before = before[..before.len() - word.len()].trim_end();
+        }
+        let after = pieces[i + 1].to_ascii_lowercase();
+        let after = after.trim_start();
+        let name = pieces[i].trim();
+        if !name.is_empty()
+            && name.len() < 100
+            && REGISTRY_WORDS
+                .iter()
+                .any(|word| before.ends_with(word) || after.starts_with(word))
+        {
+            names.push(name.to_string());
+        }
+    }
+    for word in sentence.split_whitespace() {
+        if let Some((_, path)) = word.split_once("iana.org/assignments/") {
+            let name = path
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+                .next()
+                .unwrap_or("");
+            if !name.is_empty() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+fn sentence_values(sentence: &str) -> Vec<String> {
+    let words = sentence
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .collect::<Vec<&str>>();
+    let mut values = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if !is_value(word) {
+            continue;
+        }
+        let previous = i
+            .checked_sub(1)
+            .map(|j| words[j])
+            .filter(|previous| VALUE_WORDS.contains(&previous.to_ascii_lowercase().as_str()));
+        match previous {
+            Some(previous) if previous.eq_ignore_ascii_case("point") && i >= 2 => {
+                values.push(format!("{} {} {}", words[i - 2], previous, word))
+            }
+            Some(previous) => values.push(format!("{} {}", previous, word)),
+            None if word.starts_with("TBD") => values.push(word.to_string()),
+            None => {}
+        }
+    }
+    values
+}
+
+fn is_value(word: &str) -> bool {
+    !word.is_empty()
+        && (word.chars().all(|c| c.is_ascii_digit())
+            || word
+                .strip_prefix("0x")
+                .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            || word.starts_with("TBD"))
+}
+
+fn field_value(line: &str) -> Option<String> {
+    let (key, value) = line.trim().split_once(": ")?;
+    let lower = key.to_ascii_lowercase();
+    if key.split_whitespace().count() <= 4
+        && !value.trim().is_empty()
+        && FIELD_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix))
+    {
+        Some(format!("{}: {}", key, value.trim()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::{resource_path, scratch_dir};
+
+    const BODY: &str = "\
+1.  Introduction
+
+   Widgets use the \"BGP Path Attributes\" registry.
+
+7.  IANA Considerations
+
+7.1.  BGP Path Attribute
+
+   IANA has assigned value 42 (WIDGET) in the \"BGP Path Attributes\"
+   registry.
+
+7.2.  Widget Types
+
+   IANA is requested to create a new registry called \"Widget Types\".
+   Initial values are:
+
+      Type name: red
+      Type name: green
+
+   Code point TBD1 is reserved.
+
+8.  Security Considerations
+
+   Widgets are secure.
+";
+
+    #[test]
+    fn test_parse_considerations() {
+        let considerations = Considerations::parse(&Section::parse(BODY)).unwrap();
+        assert_eq!(considerations.section, "7");
+        assert!(!considerations.no_actions);
+        assert_eq!(
+            considerations.registries,
+            vec![
+                Registry {
+                    name: "BGP Path Attributes".to_string(),
+                    section: "7.1".to_string(),
+                    values: vec!["value 42".to_string()],
+                },
+                Registry {
+                    name: "Widget Types".to_string(),
+                    section: "7.2".to_string(),
+                    values: vec![
+                        "Code point TBD1".to_string(),
+                        "Type name: red".to_string(),
+                        "Type name: green".to_string()
+                    ],
+                },
+            ]
+        );
+        assert!(considerations
+            .fmt_table()
+            .starts_with("7.1        BGP Path Attributes\n             value 42\n"));
+        assert_eq!(
+            considerations.to_json()["registries"][1]["values"][0],
+            json!("Code point TBD1")
+        );
+    }
+
+    #[test]
+    fn test_no_actions() {
+        let body = "1.  IANA Considerations\n\n   This document has no IANA actions.\n";
+        let considerations = Considerations::parse(&Section::parse(body)).unwrap();
+        assert!(considerations.no_actions);
+        assert_eq!(considerations.fmt_table(), "No IANA actions");
+        assert!(Considerations::parse(&Section::parse("1.  Introduction\n")).is_none());
+    }
+
+    #[test]
+    fn test_registry_names() {
+        assert_eq!(
+            registry_names("IANA has added \"foo\" to the registry entitled \"Foo Bar\"."),
+            vec!["Foo Bar"]
+        );
+        assert_eq!(
+            registry_names("See <https://www.iana.org/assignments/bgp-parameters/x.xhtml>."),
+            vec!["bgp-parameters"]
+        );
+        assert!(registry_names("The \"MUST\" key word registry is not real").is_empty());
+        assert_eq!(fold("Widget  Types Registry"), "widget types");
+    }
+
+    #[test]
+    fn test_iana_index() {
+        let dir = scratch_dir("iana-index");
+        let cache = dir.join(IANA_CACHE);
+        let collection = Collection::from_dir(resource_path("")).unwrap();
+        let index = IanaIndex::new(collection.clone(), 2, &cache);
+        let entries = index.find("Sieve-Notification");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].docs.len(), 1);
+        assert_eq!(entries[0].docs[0].id(), "rfc6468");
+        assert_eq!(
+            IanaIndex::to_json(&entries)[0]["documents"],
+            json!(["rfc6468"])
+        );
+        assert!(index.find("no such registry").is_empty());
+
+        let text = fs::read_to_string(&cache).unwrap();
+        assert!(text.contains("sieve-notification"));
+        fs::write(
+            &cache,
+            text.replace("sieve-notification", "Cached Registry"),
+        )
+        .unwrap();
+        let index = IanaIndex::new(collection, 2, &cache);
+        assert_eq!(index.find("cached registry")[0].docs[0].id(), "rfc6468");
+        assert!(index.find("Sieve-Notification").is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
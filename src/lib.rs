@@ -4,15 +4,38 @@ extern crate kuchiki;
 extern crate lazycell;
 extern crate num_cpus;
 extern crate pipeliner;
+extern crate ratatui;
+extern crate roxmltree;
+extern crate serde_json;
+extern crate tiny_http;
 
+mod annotate;
+mod author;
+mod browse;
+mod cite;
 mod cli;
 mod cmd;
 mod collection;
 mod document;
+mod errata;
 mod errors;
+mod extract;
+mod history;
+mod iana;
+mod mirror;
+mod overlay;
+mod profile;
+mod prune;
+mod requirements;
+mod section;
+mod serve;
+mod stats;
+mod sync;
+mod verify;
+mod wg;
 
 #[cfg(test)]
 mod test;
 
 pub use cli::{Cli, Defaults};
-pub use errors::Result;
+pub use errors::{Error, Result};
@@ -1,7 +1,11 @@
-use rfz::{Cli, Defaults, Result};
+use std::process;
 
-fn main() -> Result<()> {
-    let defaults = Defaults::get()?;
-    let cli = Cli::init(&defaults);
-    cli.run()
+use rfz::{Cli, Defaults};
+
+fn main() {
+    let result = Defaults::get().and_then(|defaults| Cli::init(&defaults).run());
+    if let Err(e) = result {
+        eprintln!("rfz: {}", e);
+        process::exit(e.exit_code());
+    }
 }
@@ -0,0 +1,341 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::document::Document;
+use crate::errata::ERRATA;
+use crate::errors::{Error, Result};
+use crate::overlay::RFC_INDEX;
+
+pub const LOCK: &str = ".sync.lock";
+pub const SYNC_LIST: &str = ".sync-list";
+pub const CURRENT: &str = "current";
+pub const SNAPSHOTS: &str = ".snapshots";
+
+const KEEP_SNAPSHOTS: usize = 2;
+
+pub fn snapshot(dir: &Path) -> PathBuf {
+    let current = dir.join(CURRENT);
+    if current.is_dir() {
+        if let Ok(path) = fs::canonicalize(current) {
+            return path;
+        }
+    }
+    dir.to_path_buf()
+}
+
+#[derive(Debug)]
+pub struct Lock(PathBuf);
+
+impl Lock {
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        if let Err(e) = fs::create_dir_all(dir) {
+            return Err(Error::SyncError(e));
+        }
+        let path = dir.join(LOCK);
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", process::id()).ok();
+                    return Ok(Lock(path));
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if !is_stale(&path) {
+                        break;
+                    }
+                    fs::remove_file(&path).ok();
+                }
+                Err(e) => return Err(Error::SyncError(e)),
+            }
+        }
+        let owner = fs::read_to_string(&path).unwrap_or_default();
+        Err(Error::LockError(format!(
+            "Another sync (pid {}) holds the lock '{}'; remove it if no sync is running",
+            owner.trim(),
+            path.to_string_lossy()
+        )))
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.0).ok();
+    }
+}
+
+#[cfg(unix)]
+fn is_stale(path: &Path) -> bool {
+    let proc = Path::new("/proc");
+    match fs::read_to_string(path).map(|pid| pid.trim().parse::<u32>()) {
+        Ok(Ok(pid)) => proc.is_dir() && !proc.join(pid.to_string()).exists(),
+        _ => false,
+    }
+}
+
+// Without a way to tell whether the owner is still running, a lock or pin is
+// only ever removed by its owner.
+#[cfg(not(unix))]
+fn is_stale(_path: &Path) -> bool {
+    false
+}
+
+#[derive(Debug)]
+pub struct Pin {
+    path: PathBuf,
+    pin: Option<PathBuf>,
+}
+
+impl Pin {
+    pub fn acquire(dir: &Path) -> Self {
+        let mut path = snapshot(dir);
+        for _ in 0..3 {
+            if !dir.join(CURRENT).is_dir() {
+                break;
+            }
+            let pin = pin_path(&path, process::id());
+            if fs::write(&pin, format!("{}\n", process::id())).is_err() {
+                break;
+            }
+            if path.is_dir() {
+                return Pin {
+                    path,
+                    pin: Some(pin),
+                };
+            }
+            fs::remove_file(&pin).ok();
+            path = snapshot(dir);
+        }
+        Pin { path, pin: None }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Pin {
+    fn drop(&mut self) {
+        if let Some(pin) = &self.pin {
+            fs::remove_file(pin).ok();
+        }
+    }
+}
+
+fn pin_prefix(snapshot: &Path) -> String {
+    let name = snapshot.file_name().unwrap_or_default().to_string_lossy();
+    format!(".{}.", name)
+}
+
+fn pin_path(snapshot: &Path, pid: u32) -> PathBuf {
+    snapshot.with_file_name(format!("{}{}", pin_prefix(snapshot), pid))
+}
+
+fn is_pinned(snapshot: &Path) -> io::Result<bool> {
+    let prefix = pin_prefix(snapshot);
+    let mut pinned = false;
+    for entry in fs::read_dir(snapshot.parent().unwrap())? {
+        let entry = entry?;
+        if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+            continue;
+        }
+        if is_stale(&entry.path()) {
+            fs::remove_file(entry.path()).ok();
+        } else {
+            pinned = true;
+        }
+    }
+    Ok(pinned)
+}
+
+pub struct Stage {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl Stage {
+    pub fn new(dir: &Path) -> Result<Self> {
+        if cfg!(not(unix)) {
+            return Err(Error::CliError(
+                "Staged syncs are only supported on unix platforms".to_string(),
+            ));
+        }
+        let name = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_nanos());
+        let path = dir.join(SNAPSHOTS).join(name.to_string());
+        if let Err(e) = fs::create_dir_all(dir.join(SNAPSHOTS)).and_then(|_| fs::create_dir(&path))
+        {
+            return Err(Error::SyncError(e));
+        }
+        let stage = Stage {
+            dir: dir.to_path_buf(),
+            path,
+        };
+        if let Err(e) = stage.seed() {
+            stage.abort();
+            return Err(Error::SyncError(e));
+        }
+        Ok(stage)
+    }
+
+    fn seed(&self) -> io::Result<()> {
+        for path in files(&snapshot(&self.dir))? {
+            fs::hard_link(&path, self.path.join(path.file_name().unwrap()))?;
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn commit(self) -> Result<()> {
+        self.swap().map_err(Error::SyncError)
+    }
+
+    fn swap(&self) -> io::Result<()> {
+        let current = self.dir.join(CURRENT);
+        let migrate = !current.is_dir();
+        let tmp = self.dir.join(format!(".{}.tmp", CURRENT));
+        fs::remove_file(&tmp).ok();
+        #[cfg(unix)]
+        symlink(self.path.strip_prefix(&self.dir).unwrap(), &tmp)?;
+        fs::rename(&tmp, &current)?;
+        if migrate {
+            for path in files(&self.dir)? {
+                fs::remove_file(path)?;
+            }
+        }
+        let mut snapshots = fs::read_dir(self.dir.join(SNAPSHOTS))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<PathBuf>>();
+        snapshots.sort();
+        let old = snapshots.len().saturating_sub(KEEP_SNAPSHOTS);
+        for path in &snapshots[..old] {
+            if !is_pinned(path)? {
+                fs::remove_dir_all(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn abort(self) {
+        fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+fn files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            path.is_file()
+                && !path.is_symlink()
+                && (name == RFC_INDEX || name == ERRATA || is_document(path))
+        })
+        .collect())
+}
+
+// Whether a mirror file is a document, as opposed to the metadata files
+// kept alongside them.
+pub fn is_document(path: &Path) -> bool {
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return false,
+    };
+    !name.starts_with('.')
+        && name != RFC_INDEX
+        && name != ERRATA
+        && Document::from_path(path.to_owned()).is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::annotate::ANNOTATIONS;
+    use crate::test::scratch_dir;
+
+    #[test]
+    fn test_lock() -> Result<()> {
+        let dir = scratch_dir("lock");
+        let lock = Lock::acquire(&dir)?;
+        assert!(matches!(Lock::acquire(&dir), Err(Error::LockError(_))));
+        drop(lock);
+        assert!(!dir.join(LOCK).exists());
+        fs::write(dir.join(LOCK), format!("{}\n", u32::MAX)).unwrap();
+        let lock = Lock::acquire(&dir)?;
+        drop(lock);
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage() -> Result<()> {
+        let dir = scratch_dir("stage");
+        fs::write(dir.join("rfc1.html"), "old").unwrap();
+        fs::write(dir.join(ANNOTATIONS), "rfc1 star\n").unwrap();
+        fs::write(dir.join("notes.md"), "mine").unwrap();
+        fs::write(dir.join(RFC_INDEX), "<rfc-index/>").unwrap();
+        assert_eq!(snapshot(&dir), dir);
+
+        let stage = Stage::new(&dir)?;
+        assert_eq!(
+            fs::read_to_string(stage.path().join("rfc1.html")).unwrap(),
+            "old"
+        );
+        fs::write(stage.path().join("rfc2.html"), "new").unwrap();
+        assert!(!dir.join(CURRENT).exists());
+        stage.commit()?;
+        let first = snapshot(&dir);
+        assert!(first.starts_with(fs::canonicalize(dir.join(SNAPSHOTS)).unwrap()));
+        assert!(first.join("rfc2.html").is_file());
+        assert!(!dir.join("rfc1.html").exists());
+        assert!(dir.join(ANNOTATIONS).is_file());
+        assert!(!first.join(ANNOTATIONS).exists());
+        assert!(dir.join("notes.md").is_file());
+        assert!(!first.join("notes.md").exists());
+        assert!(!dir.join(RFC_INDEX).exists());
+        assert!(first.join(RFC_INDEX).is_file());
+
+        let stage = Stage::new(&dir)?;
+        fs::remove_file(stage.path().join("rfc1.html")).unwrap();
+        stage.abort();
+        assert_eq!(snapshot(&dir), first);
+        assert!(first.join("rfc1.html").is_file());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_pin() -> Result<()> {
+        let dir = scratch_dir("pin");
+        fs::write(dir.join("rfc1.html"), "old").unwrap();
+        let pin = Pin::acquire(&dir);
+        assert_eq!(pin.path(), dir);
+        drop(pin);
+
+        Stage::new(&dir)?.commit()?;
+        let pin = Pin::acquire(&dir);
+        let first = pin.path().to_path_buf();
+        assert_eq!(first, snapshot(&dir));
+        for _ in 0..2 {
+            Stage::new(&dir)?.commit()?;
+        }
+        assert!(first.join("rfc1.html").is_file());
+        drop(pin);
+        fs::write(pin_path(&first, u32::MAX), format!("{}\n", u32::MAX)).unwrap();
+        Stage::new(&dir)?.commit()?;
+        assert!(!first.exists());
+        assert!(!pin_path(&first, u32::MAX).exists());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
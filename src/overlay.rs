@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Node, ParsingOptions};
+
+use crate::document::{normalize_id, Metadata, ERRATA_COUNTS};
+use crate::errata::{self, Errata};
+use crate::errors::{Error, Result};
+
+pub const RFC_INDEX: &str = "rfc-index.xml";
+
+const RELATIONS: &[(&str, &str)] = &[
+    ("obsoletes", "Relation.Obsoletes"),
+    ("obsoleted-by", "Relation.ObsoletedBy"),
+    ("updates", "Relation.Updates"),
+    ("updated-by", "Relation.UpdatedBy"),
+    ("is-also", "Relation.IsPartOf"),
+    ("see-also", "Relation.SeeAlso"),
+];
+
+const FIELDS: &[(&str, &str)] = &[
+    ("current-status", "Status"),
+    ("stream", "Stream"),
+    ("wg_acronym", "WorkingGroup"),
+    ("page-count", "Format.Extent"),
+    ("errata-url", "Errata"),
+    ("doi", "Identifier.DOI"),
+];
+
+const SERIES: &[&str] = &["bcp-entry", "std-entry", "fyi-entry"];
+
+#[derive(Debug, Default)]
+pub struct Overlay(HashMap<String, Metadata>);
+
+impl Overlay {
+    pub fn from_dir(dir: &Path) -> Self {
+        let mut overlay = Overlay::default();
+        let path = dir.join(RFC_INDEX);
+        if path.is_file() {
+            // A corrupt index must not stop 'sync --rfc-index' from replacing it.
+            if let Err(e) = overlay.merge_rfc_index(&path) {
+                eprintln!("{}", e);
+                overlay = Overlay::default();
+            }
+        }
+        overlay
+    }
+
+    pub fn with_errata(mut self, dir: &Path) -> Self {
+        let result = match Errata::from_dir(dir) {
+            Ok(Some(errata)) => self.merge_errata(&errata),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+        self
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Metadata> {
+        self.0.get(id)
+    }
+
+    pub fn members(&self, id: &str) -> Option<Vec<&String>> {
+        match self.get(id)?.values("Relation.HasPart") {
+            members if members.is_empty() => None,
+            members => Some(members),
+        }
+    }
+
+    fn merge_rfc_index(&mut self, path: &Path) -> Result<()> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return Err(Error::FileReadError(e).in_document(path)),
+        };
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let xml = match roxmltree::Document::parse_with_options(&text, options) {
+            Ok(xml) => xml,
+            Err(e) => {
+                return Err(Error::XmlParseError(format!(
+                    "Failed to parse RFC index {:?}: {}",
+                    path, e
+                )))
+            }
+        };
+        for entry in xml.root_element().children().filter(|n| n.is_element()) {
+            let id = match child_text(entry, "doc-id") {
+                Some(id) => normalize_id(&id),
+                None => continue,
+            };
+            let meta = self.0.entry(id).or_default();
+            if SERIES.iter().any(|tag| entry.has_tag_name(*tag)) {
+                if let Some(title) = child_text(entry, "title") {
+                    meta.insert("Title".to_string(), title)?;
+                }
+                if let Some(members) = entry.children().find(|n| n.has_tag_name("is-also")) {
+                    for value in members.children().filter(|n| n.has_tag_name("doc-id")) {
+                        meta.insert(
+                            "Relation.HasPart".to_string(),
+                            normalize_id(value.text().unwrap_or("")),
+                        )?;
+                    }
+                }
+                continue;
+            }
+            for (tag, key) in FIELDS {
+                if let Some(value) = child_text(entry, tag) {
+                    meta.insert(key.to_string(), value)?;
+                }
+            }
+            for (tag, key) in RELATIONS {
+                if let Some(relation) = entry.children().find(|n| n.has_tag_name(*tag)) {
+                    for value in relation.children().filter(|n| n.has_tag_name("doc-id")) {
+                        meta.insert(key.to_string(), normalize_id(value.text().unwrap_or("")))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_errata(&mut self, errata: &Errata) -> Result<()> {
+        for (id, entries) in errata.iter() {
+            for (key, status) in ERRATA_COUNTS {
+                let n = errata::count(entries, status);
+                if n > 0 {
+                    self.0
+                        .entry(id.to_owned())
+                        .or_default()
+                        .insert(key.to_string(), n.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .and_then(|n| n.text())
+        .map(|text| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::{resource_path, scratch_dir};
+
+    #[test]
+    fn test_rfc_index_overlay() -> Result<()> {
+        let overlay = Overlay::from_dir(&resource_path(""));
+        assert!(overlay
+            .get("rfc6468")
+            .is_none_or(|meta| meta.get("Errata.Verified").is_none()));
+        let overlay = overlay.with_errata(&resource_path(""));
+        let meta = overlay.get("rfc1771").unwrap().fmt_summary();
+        for string in &[
+            "Relation.ObsoletedBy:\nrfc4271",
+            "Relation.Obsoletes:\nrfc1654",
+            "Status:\nDRAFT STANDARD",
+            "Format.Extent:\n57",
+            "Identifier.DOI:\n10.17487/RFC1771",
+        ] {
+            assert!(meta.contains(string), "'{}' not found in output", string);
+        }
+        let meta = overlay.get("rfc2119").unwrap().fmt_summary();
+        assert!(meta.contains("Relation.IsPartOf:\nbcp14"));
+        assert!(meta.contains("Relation.UpdatedBy:\nrfc8174"));
+        assert_eq!(
+            overlay.members("bcp14"),
+            Some(vec![&"rfc2119".to_string(), &"rfc8174".to_string()])
+        );
+        assert_eq!(overlay.members("rfc2119"), None);
+        let meta = overlay.get("rfc6468").unwrap();
+        assert_eq!(meta.values("Errata.Verified"), vec!["1"]);
+        assert_eq!(meta.values("Errata.Reported"), vec!["1"]);
+        assert!(overlay
+            .get("rfc8174")
+            .unwrap()
+            .get("Errata.Verified")
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_errata() -> Result<()> {
+        let dir = scratch_dir("corrupt-errata");
+        fs::write(dir.join(crate::errata::ERRATA), "<html>503</html>").unwrap();
+        let overlay = Overlay::from_dir(&dir).with_errata(&dir);
+        assert!(overlay.get("rfc6468").is_none());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_rfc_index() {
+        let dir = scratch_dir("corrupt-rfc-index");
+        fs::write(dir.join(RFC_INDEX), "<rfc-index><rfc-entry><doc-id>RFC2119").unwrap();
+        let overlay = Overlay::from_dir(&dir);
+        assert!(overlay.get("rfc2119").is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_no_rfc_index() -> Result<()> {
+        let overlay = Overlay::from_dir(&resource_path("not-found"));
+        assert!(overlay.get("rfc2119").is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::collection::{Collection, TYPES};
+use crate::document::Document;
+use crate::errors::{Error, Result};
+use crate::overlay::Overlay;
+
+pub const PROFILES: &str = "profiles";
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Profile {
+    pub types: Vec<String>,
+    pub groups: Vec<String>,
+    pub revisions: Option<u8>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Profile {
+    pub fn load(path: &Path, name: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Profile::parse(&content, name),
+            Err(e) => Err(Error::CliError(format!(
+                "Failed to read sync profiles from '{}': {}",
+                path.to_string_lossy(),
+                e
+            ))),
+        }
+    }
+
+    fn parse(content: &str, name: &str) -> Result<Self> {
+        let mut profile = None;
+        let mut section = None;
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(header.trim());
+                if section == Some(name) {
+                    profile = Some(Profile::default());
+                }
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.split_whitespace()),
+                None => {
+                    return Err(Error::CliError(format!(
+                        "Expected 'key = value' on line {} of sync profiles",
+                        i + 1
+                    )))
+                }
+            };
+            if section != Some(name) {
+                continue;
+            }
+            let profile = profile.as_mut().unwrap();
+            let values = value.map(|v| v.to_string()).collect::<Vec<String>>();
+            match key {
+                "type" => {
+                    if let Some(t) = values.iter().find(|t| !TYPES.contains(&t.as_str())) {
+                        return Err(Error::CliError(format!(
+                            "Unknown document type '{}' on line {} of sync profiles",
+                            t,
+                            i + 1
+                        )));
+                    }
+                    profile.types.extend(values)
+                }
+                "group" => profile.groups.extend(values),
+                "revisions" => match values.first().map(|v| u8::from_str(v)) {
+                    Some(Ok(n)) if n > 0 => profile.revisions = Some(n),
+                    _ => {
+                        return Err(Error::CliError(format!(
+                            "Expected a positive number of revisions on line {} of sync profiles",
+                            i + 1
+                        )))
+                    }
+                },
+                "include" => profile.include.extend(values),
+                "exclude" => profile.exclude.extend(values),
+                _ => {
+                    return Err(Error::CliError(format!(
+                        "Unknown key '{}' on line {} of sync profiles",
+                        key,
+                        i + 1
+                    )))
+                }
+            }
+        }
+        match profile {
+            Some(profile) => Ok(profile),
+            None => Err(Error::CliError(format!(
+                "Failed to find sync profile '{}'",
+                name
+            ))),
+        }
+    }
+
+    pub fn merge(mut self, other: Profile) -> Self {
+        self.types.extend(other.types);
+        self.groups.extend(other.groups);
+        self.revisions = other.revisions.or(self.revisions);
+        self.include.extend(other.include);
+        self.exclude.extend(other.exclude);
+        self
+    }
+
+    pub fn is_selective(&self) -> bool {
+        !self.types.is_empty() || !self.groups.is_empty() || self.revisions.is_some()
+    }
+
+    pub fn rules(&self, formats: &[&str]) -> Vec<String> {
+        let mut rules = self
+            .exclude
+            .iter()
+            .map(|pattern| format!("--exclude={}", pattern))
+            .collect::<Vec<String>>();
+        if self.include.is_empty() {
+            rules.extend(
+                formats
+                    .iter()
+                    .map(|format| format!("--include=*.{}", format)),
+            );
+        } else {
+            rules.extend(
+                self.include
+                    .iter()
+                    .map(|pattern| format!("--include={}", pattern)),
+            );
+        }
+        rules.push("--exclude=**".to_string());
+        rules
+    }
+
+    pub fn select(&self, names: Vec<String>, overlay: &Overlay) -> Vec<String> {
+        let docs = names
+            .iter()
+            .filter_map(|name| Document::from_path(PathBuf::from(name)))
+            .filter_map(|result| result.ok())
+            .collect::<Collection>()
+            .with_overlay(overlay);
+        let types = if self.types.is_empty() {
+            None
+        } else {
+            Some(self.types.iter().map(|t| t.as_str()).collect())
+        };
+        let selected = docs
+            .filter_types(types)
+            .newest(self.revisions.unwrap_or(u8::MAX))
+            .into_iter()
+            .filter(|doc| match doc.group() {
+                Some(group) if !self.groups.is_empty() => self
+                    .groups
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(group)),
+                _ => self.groups.is_empty() || !doc.id().starts_with("draft"),
+            })
+            .map(|doc| (doc.id().to_owned(), *doc.version()))
+            .collect::<HashSet<(String, i8)>>();
+        names
+            .into_iter()
+            .filter(|name| match Document::from_path(PathBuf::from(name)) {
+                Some(Ok(doc)) => selected.contains(&(doc.id().to_owned(), *doc.version())),
+                _ => false,
+            })
+            .collect()
+    }
+}
+
+pub fn parse_listing(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .filter(|line| line.starts_with('-'))
+        .filter_map(|line| line.split_whitespace().nth(4))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::resource_path;
+
+    const CONFIG: &str = "
+# Lean mirror for slow links
+[laptop]
+type = rfc draft
+group = sidrops
+revisions = 1
+exclude = *-00.html
+
+[everything]
+include = *.html *.txt
+";
+
+    fn listing() -> Vec<String> {
+        [
+            "draft-ietf-sidrops-rpkimaxlen-04.html",
+            "draft-ietf-sidrops-rpkimaxlen-05.html",
+            "draft-ietf-sidrops-rpkimaxlen-05.txt",
+            "draft-ietf-grow-bgp-reject-08.html",
+            "draft-leiba-rfc2119-update-00.html",
+            "rfc6468.html",
+            "rfc8174.html",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_parse_profiles() -> Result<()> {
+        let laptop = Profile::parse(CONFIG, "laptop")?;
+        assert_eq!(laptop.types, vec!["rfc", "draft"]);
+        assert_eq!(laptop.groups, vec!["sidrops"]);
+        assert_eq!(laptop.revisions, Some(1));
+        assert_eq!(laptop.exclude, vec!["*-00.html"]);
+        assert!(laptop.is_selective());
+        let everything = Profile::parse(CONFIG, "everything")?;
+        assert!(!everything.is_selective());
+        assert_eq!(
+            everything.rules(&["html"]),
+            vec!["--include=*.html", "--include=*.txt", "--exclude=**"]
+        );
+        assert!(Profile::parse(CONFIG, "missing").is_err());
+        assert!(Profile::parse("[bad]\ntype = fyi\n", "bad").is_err());
+        assert!(Profile::parse("[bad]\nrevisions = 0\n", "bad").is_err());
+        assert!(Profile::parse("[bad]\nnonsense\n", "bad").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_profiles() -> Result<()> {
+        let profile = Profile::parse(CONFIG, "laptop")?.merge(Profile {
+            groups: vec!["grow".to_string()],
+            revisions: Some(2),
+            ..Profile::default()
+        });
+        assert_eq!(profile.groups, vec!["sidrops", "grow"]);
+        assert_eq!(profile.revisions, Some(2));
+        assert_eq!(
+            profile.rules(&["html", "xml"]),
+            vec![
+                "--exclude=*-00.html",
+                "--include=*.html",
+                "--include=*.xml",
+                "--exclude=**"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_select() -> Result<()> {
+        let overlay = Overlay::default();
+        let profile = Profile::parse(CONFIG, "laptop")?;
+        let mut selected = profile.select(listing(), &overlay);
+        selected.sort();
+        assert_eq!(
+            selected,
+            vec![
+                "draft-ietf-sidrops-rpkimaxlen-05.html",
+                "draft-ietf-sidrops-rpkimaxlen-05.txt",
+                "rfc6468.html",
+                "rfc8174.html",
+            ]
+        );
+        let rfcs = Profile {
+            types: vec!["rfc".to_string()],
+            ..Profile::default()
+        };
+        assert_eq!(
+            rfcs.select(listing(), &overlay),
+            vec!["rfc6468.html", "rfc8174.html"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_series() -> Result<()> {
+        let overlay = Overlay::from_dir(&resource_path(""));
+        let bcp = Profile {
+            types: vec!["bcp".to_string()],
+            ..Profile::default()
+        };
+        assert_eq!(bcp.select(listing(), &overlay), vec!["rfc8174.html"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_listing() {
+        let listing = "receiving file list ... done\n\
+            drwxr-xr-x          4,096 2021/01/01 00:00:00 .\n\
+            -rw-r--r--         55,511 2012/02/23 17:32:41 rfc6468.html\n\
+            -rw-r--r--        102,386 2020/11/02 12:00:00 draft-ietf-sidrops-rpkimaxlen-05.html\n";
+        assert_eq!(
+            parse_listing(listing),
+            vec!["rfc6468.html", "draft-ietf-sidrops-rpkimaxlen-05.html"]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::collection::Collection;
+use crate::document::{fmt_id, normalize_id, split_revision, Document, DraftState};
+
+pub const PRUNED: &str = ".pruned";
+
+#[derive(Debug, Default)]
+pub struct Retention {
+    pub keep: Option<u8>,
+    pub replaced: bool,
+    pub expired: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    Superseded,
+    Replaced(String),
+    Expired(i64),
+}
+
+impl Reason {
+    pub fn describe(&self) -> String {
+        match self {
+            Reason::Superseded => "superseded by a later revision".to_string(),
+            Reason::Replaced(id) => format!("replaced by {}", fmt_id(id)),
+            Reason::Expired(days) => format!("expired {} days ago", days),
+        }
+    }
+}
+
+impl Retention {
+    pub fn select(&self, collection: &Collection, jobs: usize) -> Vec<(Document, Reason)> {
+        let mut reasons = HashMap::new();
+        if self.replaced || self.expired.is_some() {
+            for doc in &collection.newest(1).with_meta(jobs).with_lineage() {
+                let reason = match doc.state() {
+                    Some(DraftState::Replaced(id)) if self.replaced && id.starts_with("rfc") => {
+                        Reason::Replaced(id)
+                    }
+                    Some(DraftState::Expired) => match (self.expired, doc.days_expired()) {
+                        (Some(min), Some(days)) if days > min => Reason::Expired(days),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                reasons.insert(doc.id().to_owned(), reason);
+            }
+        }
+        let superseded = match self.keep {
+            Some(count) => collection
+                .superseded(count)
+                .into_iter()
+                .map(|doc| doc.path().to_owned())
+                .collect(),
+            None => HashSet::new(),
+        };
+        let mut docs = Vec::new();
+        for doc in collection {
+            if let Some(reason) = reasons.get(doc.id()) {
+                docs.push((doc.to_owned(), reason.clone()));
+            } else if superseded.contains(doc.path()) {
+                docs.push((doc.to_owned(), Reason::Superseded));
+            }
+        }
+        docs.sort_by(|a, b| a.0.path().cmp(b.0.path()));
+        docs
+    }
+}
+
+pub fn remove(dir: &Path, docs: &[(Document, Reason)]) -> io::Result<()> {
+    let mut pruned = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(PRUNED))?;
+    for (doc, _) in docs {
+        if let Some(name) = doc.path().file_name() {
+            writeln!(pruned, "{}", name.to_string_lossy())?;
+        }
+        fs::remove_file(doc.path())?;
+    }
+    Ok(())
+}
+
+// Drops the given documents, or single draft revisions, from the exclude list
+// so that the next sync fetches them again.
+pub fn forget(dir: &Path, ids: &[&str], dry_run: bool) -> io::Result<Vec<String>> {
+    let path = dir.join(PRUNED);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let ids = ids
+        .iter()
+        .map(|id| normalize_id(id))
+        .collect::<Vec<String>>();
+    let (forgotten, kept): (Vec<&str>, Vec<&str>) = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .partition(
+            |line| match Document::from_path(PathBuf::from(line.trim())) {
+                Some(Ok(doc)) => ids.iter().any(|id| match split_revision(id) {
+                    (id, Some(version)) => doc.id() == id && *doc.version() == version,
+                    (id, None) => doc.id() == id,
+                }),
+                _ => false,
+            },
+        );
+    if !forgotten.is_empty() && !dry_run {
+        let tmp = dir.join(format!("{}.tmp", PRUNED));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp)?;
+        for line in &kept {
+            writeln!(file, "{}", line)?;
+        }
+        drop(file);
+        fs::rename(&tmp, &path)?;
+    }
+    Ok(forgotten.into_iter().map(String::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::errors::Result;
+    use crate::test::{resource_path, scratch_dir};
+
+    fn names(docs: &[(Document, Reason)]) -> Vec<String> {
+        docs.iter()
+            .map(|(doc, _)| {
+                doc.path()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_keep_revisions() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let retention = Retention {
+            keep: Some(1),
+            ..Retention::default()
+        };
+        let docs = retention.select(&collection, 1);
+        assert_eq!(names(&docs), vec!["draft-ietf-sidrops-rpkimaxlen-04.html"]);
+        assert_eq!(docs[0].1, Reason::Superseded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_expired() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let retention = Retention {
+            expired: Some(30),
+            ..Retention::default()
+        };
+        let docs = retention.select(&collection, 1);
+        assert_eq!(
+            names(&docs),
+            vec![
+                "draft-ietf-sidrops-rpkimaxlen-04.html",
+                "draft-ietf-sidrops-rpkimaxlen-05.html"
+            ]
+        );
+        assert!(matches!(docs[0].1, Reason::Expired(days) if days > 30));
+        let retention = Retention {
+            expired: Some(i64::MAX),
+            ..Retention::default()
+        };
+        assert!(retention.select(&collection, 1).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replaced() -> Result<()> {
+        let dir = scratch_dir("prune-replaced");
+        fs::copy(resource_path("rfc8212.xml"), dir.join("rfc8212.xml")).unwrap();
+        fs::copy(
+            resource_path("draft-ietf-sidrops-rpkimaxlen-05.html"),
+            dir.join("draft-ietf-grow-bgp-reject-08.html"),
+        )
+        .unwrap();
+        let collection = Collection::from_dir(dir.to_owned())?;
+        let retention = Retention {
+            replaced: true,
+            ..Retention::default()
+        };
+        let docs = retention.select(&collection, 1);
+        assert_eq!(names(&docs), vec!["draft-ietf-grow-bgp-reject-08.html"]);
+        assert_eq!(docs[0].1.describe(), "replaced by RFC 8212");
+        remove(&dir, &docs).unwrap();
+        assert!(!dir.join("draft-ietf-grow-bgp-reject-08.html").exists());
+        assert_eq!(
+            fs::read_to_string(dir.join(PRUNED)).unwrap(),
+            "draft-ietf-grow-bgp-reject-08.html\n"
+        );
+        fs::write(
+            dir.join(PRUNED),
+            "draft-ietf-grow-bgp-reject-07.html\ndraft-ietf-grow-bgp-reject-08.html\nrfc1.html\n",
+        )
+        .unwrap();
+        assert_eq!(
+            forget(&dir, &["draft-ietf-grow-bgp-reject-07"], false).unwrap(),
+            vec!["draft-ietf-grow-bgp-reject-07.html"]
+        );
+        assert_eq!(forget(&dir, &["rfc1"], true).unwrap(), vec!["rfc1.html"]);
+        assert_eq!(
+            forget(&dir, &["draft-ietf-grow-bgp-reject", "RFC 1"], false).unwrap(),
+            vec!["draft-ietf-grow-bgp-reject-08.html", "rfc1.html"]
+        );
+        assert_eq!(fs::read_to_string(dir.join(PRUNED)).unwrap(), "");
+        assert!(forget(&dir, &["rfc2"], false).unwrap().is_empty());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+}
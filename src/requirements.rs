@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use crate::section::{sentences, Section};
+
+pub const OUTPUTS: &[&str] = &["table", "csv", "json"];
+
+const KEYWORDS: &[&str] = &[
+    "MUST NOT",
+    "MUST",
+    "REQUIRED",
+    "SHALL NOT",
+    "SHALL",
+    "SHOULD NOT",
+    "SHOULD",
+    "NOT RECOMMENDED",
+    "RECOMMENDED",
+    "MAY",
+    "OPTIONAL",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub section: String,
+    pub keywords: Vec<&'static str>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+}
+
+impl Change {
+    fn describe(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+        }
+    }
+
+    fn marker(&self) -> &'static str {
+        match self {
+            Change::Added => "+",
+            Change::Removed => "-",
+        }
+    }
+}
+
+pub type Row<'a> = (Option<Change>, &'a Requirement);
+
+pub fn extract(sections: &[Section]) -> Vec<Requirement> {
+    let mut requirements = Vec::new();
+    for section in Section::flatten(sections) {
+        if section.title.eq_ignore_ascii_case("Table of Contents") {
+            continue;
+        }
+        let label = if section.number.is_empty() {
+            section.title.to_owned()
+        } else {
+            section.number.to_owned()
+        };
+        for paragraph in section.paragraphs() {
+            for sentence in sentences(&paragraph) {
+                let keywords = keywords(sentence);
+                if !keywords.is_empty() {
+                    requirements.push(Requirement {
+                        section: label.to_owned(),
+                        keywords,
+                        text: sentence.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    requirements
+}
+
+pub fn diff<'a>(old: &'a [Requirement], new: &'a [Requirement]) -> Vec<Row<'a>> {
+    let old_texts = old.iter().map(|r| &r.text).collect::<HashSet<&String>>();
+    let new_texts = new.iter().map(|r| &r.text).collect::<HashSet<&String>>();
+    old.iter()
+        .filter(|r| !new_texts.contains(&r.text))
+        .map(|r| (Some(Change::Removed), r))
+        .chain(
+            new.iter()
+                .filter(|r| !old_texts.contains(&r.text))
+                .map(|r| (Some(Change::Added), r)),
+        )
+        .collect()
+}
+
+pub fn fmt_table(rows: &[Row]) -> String {
+    rows.iter()
+        .map(|(change, r)| {
+            format!(
+                "{}{:<10} {:<16} {}",
+                change.map_or_else(String::new, |c| format!("{} ", c.marker())),
+                r.section,
+                r.keywords.join(","),
+                r.text
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn fmt_csv(rows: &[Row]) -> String {
+    let changes = rows.iter().any(|(change, _)| change.is_some());
+    let mut lines = vec![if changes {
+        "change,section,keywords,text".to_string()
+    } else {
+        "section,keywords,text".to_string()
+    }];
+    for (change, r) in rows {
+        let mut fields = Vec::new();
+        if changes {
+            fields.push(change.map_or("", |c| c.describe()).to_string());
+        }
+        fields.push(escape_csv(&r.section));
+        fields.push(escape_csv(&r.keywords.join(",")));
+        fields.push(escape_csv(&r.text));
+        lines.push(fields.join(","));
+    }
+    lines.join("\n")
+}
+
+pub fn to_json(rows: &[Row]) -> Value {
+    Value::Array(
+        rows.iter()
+            .map(|(change, r)| {
+                let mut value = json!({
+                    "section": r.section,
+                    "keywords": r.keywords,
+                    "text": r.text,
+                });
+                if let Some(change) = change {
+                    value["change"] = json!(change.describe());
+                }
+                value
+            })
+            .collect(),
+    )
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn keywords(sentence: &str) -> Vec<&'static str> {
+    let words = sentence
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| c.is_ascii_punctuation() && c != '"'))
+        .collect::<Vec<&str>>();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let pair = words
+            .get(i + 1)
+            .map(|next| format!("{} {}", words[i], next));
+        match KEYWORDS
+            .iter()
+            .find(|&&keyword| keyword == words[i] || pair.as_deref() == Some(keyword))
+        {
+            Some(keyword) => {
+                if !found.contains(keyword) {
+                    found.push(*keyword);
+                }
+                i += keyword.split(' ').count();
+            }
+            None => i += 1,
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BODY: &str = "\
+1.  Terminology
+
+   The key words \"MUST\", \"MUST NOT\", \"REQUIRED\", \"SHALL\", \"SHALL NOT\",
+   \"SHOULD\", \"SHOULD NOT\", \"RECOMMENDED\", \"MAY\", and \"OPTIONAL\" in this
+   document are to be interpreted as described in RFC 2119.
+
+2.  Widgets
+
+   A widget MUST NOT be blue, e.g. navy.  It SHOULD be red; it MAY be
+   green.  Widgets are useful.
+
+2.1.  Gadgets
+
+   Gadgets are NOT RECOMMENDED.
+";
+
+    #[test]
+    fn test_extract() {
+        let requirements = extract(&Section::parse(BODY));
+        assert_eq!(requirements.len(), 3);
+        assert_eq!(requirements[0].section, "2");
+        assert_eq!(requirements[0].keywords, vec!["MUST NOT"]);
+        assert_eq!(
+            requirements[0].text,
+            "A widget MUST NOT be blue, e.g. navy."
+        );
+        assert_eq!(requirements[1].keywords, vec!["SHOULD", "MAY"]);
+        assert_eq!(requirements[2].section, "2.1");
+        assert_eq!(requirements[2].keywords, vec!["NOT RECOMMENDED"]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = extract(&Section::parse(BODY));
+        let new = extract(&Section::parse(
+            &BODY.replace("MUST NOT be blue", "MUST be blue"),
+        ));
+        let rows = diff(&old, &new);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, Some(Change::Removed));
+        assert_eq!(rows[1].0, Some(Change::Added));
+        assert_eq!(rows[1].1.keywords, vec!["MUST"]);
+        assert!(fmt_table(&rows).starts_with("- 2          MUST NOT"));
+        assert_eq!(
+            fmt_csv(&rows).lines().nth(2),
+            Some("added,2,MUST,\"A widget MUST be blue, e.g. navy.\"")
+        );
+        assert_eq!(to_json(&rows)[0]["change"], json!("removed"));
+    }
+
+    #[test]
+    fn test_formats() {
+        let requirements = extract(&Section::parse(BODY));
+        let rows = requirements.iter().map(|r| (None, r)).collect::<Vec<Row>>();
+        assert_eq!(
+            fmt_csv(&rows).lines().collect::<Vec<&str>>()[..3],
+            [
+                "section,keywords,text",
+                "2,MUST NOT,\"A widget MUST NOT be blue, e.g. navy.\"",
+                "2,\"SHOULD,MAY\",It SHOULD be red; it MAY be green."
+            ]
+        );
+        let json = to_json(&rows);
+        assert_eq!(json[2]["section"], json!("2.1"));
+        assert_eq!(json[1]["keywords"], json!(["SHOULD", "MAY"]));
+        assert!(json[0].get("change").is_none());
+    }
+}
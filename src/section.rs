@@ -0,0 +1,351 @@
+const ABBREVIATIONS: &[&str] = &["e.g.", "i.e.", "etc.", "al.", "cf.", "Sec.", "vs."];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub number: String,
+    pub title: String,
+    lines: Vec<String>,
+    pub children: Vec<Section>,
+}
+
+impl Section {
+    pub fn parse(body: &str) -> Vec<Section> {
+        let mut stack: Vec<Section> = Vec::new();
+        let mut sections = Vec::new();
+        for line in strip_pages(body, true) {
+            let (number, title) = match heading(&line) {
+                Some(heading) => heading,
+                None => {
+                    if let Some(section) = stack.last_mut() {
+                        section.lines.push(line);
+                    }
+                    continue;
+                }
+            };
+            let section = Section {
+                number,
+                title,
+                lines: Vec::new(),
+                children: Vec::new(),
+            };
+            while let Some(last) = stack.last() {
+                if section.number.starts_with(&format!("{}.", last.number)) {
+                    break;
+                }
+                close(&mut stack, &mut sections);
+            }
+            stack.push(section);
+        }
+        while !stack.is_empty() {
+            close(&mut stack, &mut sections);
+        }
+        sections
+    }
+
+    pub fn level(&self) -> usize {
+        match self.number.as_str() {
+            "" => 1,
+            number => number.split('.').count(),
+        }
+    }
+
+    pub fn find<'a>(sections: &'a [Section], query: &str) -> Option<&'a Section> {
+        let query = normalize_number(query);
+        sections.iter().find_map(|section| {
+            if section.number.eq_ignore_ascii_case(&query)
+                || (section.number.is_empty() && section.title.eq_ignore_ascii_case(&query))
+            {
+                Some(section)
+            } else {
+                Section::find(&section.children, &query)
+            }
+        })
+    }
+
+    pub fn flatten(sections: &[Section]) -> Vec<&Section> {
+        sections
+            .iter()
+            .flat_map(|section| {
+                let mut flat = vec![section];
+                flat.extend(Section::flatten(&section.children));
+                flat
+            })
+            .collect()
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn paragraphs(&self) -> Vec<String> {
+        self.lines
+            .split(|line| line.is_empty())
+            .filter(|lines| !lines.is_empty())
+            .map(|lines| {
+                lines
+                    .iter()
+                    .flat_map(|line| line.split_whitespace())
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    pub fn fmt_heading(&self) -> String {
+        match self.number.as_str() {
+            "" => self.title.to_owned(),
+            number if number.starts_with(|c: char| c.is_ascii_digit()) => {
+                format!("{}.  {}", number, self.title)
+            }
+            number => format!("Appendix {}.  {}", number, self.title),
+        }
+    }
+
+    pub fn fmt_toc(&self) -> String {
+        let mut lines = vec![format!(
+            "{}{}",
+            "  ".repeat(self.level() - 1),
+            self.fmt_heading()
+        )];
+        lines.extend(self.children.iter().map(|child| child.fmt_toc()));
+        lines.join("\n")
+    }
+
+    pub fn text(&self) -> String {
+        let mut lines = vec![self.fmt_heading()];
+        lines.extend(self.lines.iter().cloned());
+        let mut text = lines.join("\n").trim_end().to_string();
+        for child in &self.children {
+            text.push_str("\n\n");
+            text.push_str(&child.text());
+        }
+        text
+    }
+}
+
+fn close(stack: &mut Vec<Section>, sections: &mut Vec<Section>) {
+    let section = stack.pop().unwrap();
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(section),
+        None => sections.push(section),
+    }
+}
+
+fn heading(line: &str) -> Option<(String, String)> {
+    if line.is_empty() || line.starts_with(char::is_whitespace) || line.contains("   ") {
+        return None;
+    }
+    let (number, title) = match line.split_once("  ") {
+        Some((number, title)) => (
+            number
+                .strip_prefix("Appendix ")
+                .unwrap_or(number)
+                .trim_end_matches('.'),
+            title.trim(),
+        ),
+        None if line.starts_with(|c: char| c.is_ascii_uppercase()) => {
+            return Some((String::new(), line.trim().to_string()))
+        }
+        None => return None,
+    };
+    let mut parts = number.split('.');
+    let valid = match parts.next() {
+        Some(first) if first.chars().all(|c| c.is_ascii_digit()) => !first.is_empty(),
+        Some(first) => first.len() == 1 && first.starts_with(|c: char| c.is_ascii_uppercase()),
+        None => false,
+    };
+    if valid && !title.is_empty() && parts.all(|part| part.parse::<u16>().is_ok()) {
+        Some((number.to_string(), title.to_string()))
+    } else {
+        None
+    }
+}
+
+fn normalize_number(number: &str) -> String {
+    let number = number.trim();
+    let number = number.strip_prefix('§').unwrap_or(number).trim_start();
+    let lower = number.to_ascii_lowercase();
+    let number = ["section", "appendix"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix).map(|_| &number[prefix.len()..]))
+        .unwrap_or(number);
+    let number = number.trim_start_matches(['.', ' ']).trim_end_matches('.');
+    match number.split_once('.') {
+        Some((first, rest)) if first.len() == 1 => {
+            format!("{}.{}", first.to_ascii_uppercase(), rest)
+        }
+        None if number.len() == 1 => number.to_ascii_uppercase(),
+        _ => number.to_string(),
+    }
+}
+
+pub fn strip_pages(body: &str, gap: bool) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut header = false;
+    let mut skip_blank = false;
+    for line in body.lines() {
+        if line.contains('\u{c}') {
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            if lines.last().is_some_and(|line| is_footer(line)) {
+                lines.pop();
+            }
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            header = true;
+            continue;
+        }
+        let line = line.trim_end();
+        if header {
+            header = false;
+            if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+                if gap {
+                    lines.push(String::new());
+                }
+                skip_blank = !gap;
+                continue;
+            }
+        }
+        if line.is_empty() && (skip_blank || (gap && lines.last().is_some_and(|l| l.is_empty()))) {
+            continue;
+        }
+        skip_blank = false;
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+pub fn sentences(paragraph: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let words = paragraph.match_indices(' ').map(|(i, _)| i);
+    for i in words {
+        let word = paragraph[start..i].rsplit(' ').next().unwrap_or("");
+        let next = paragraph[i + 1..].chars().next();
+        if word.ends_with(['.', '?', '!'])
+            && !ABBREVIATIONS.contains(&word)
+            && next.is_some_and(|c| c.is_uppercase() || c == '"' || c == '(')
+        {
+            sentences.push(paragraph[start..i].trim());
+            start = i + 1;
+        }
+    }
+    sentences.push(paragraph[start..].trim());
+    sentences.retain(|sentence| !sentence.is_empty());
+    sentences
+}
+
+fn is_footer(line: &str) -> bool {
+    line.trim_end()
+        .strip_suffix(']')
+        .and_then(|line| line.rsplit_once("[Page "))
+        .is_some_and(|(_, page)| page.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BODY: &str = "\
+Abstract
+
+   This memo describes a widget.
+
+1.  Introduction
+
+   Widgets are useful.
+
+1.1.  Terminology
+
+   The key words are used.
+
+
+
+Widget, et al.              Standards Track                    [Page 1]
+\u{c}
+RFC 9999                        Widgets                       April 2024
+
+
+2.  Widgets
+
+   A widget is
+   a thing.
+
+Appendix A.  Acknowledgements
+
+   Thanks.
+";
+
+    #[test]
+    fn test_parse_sections() {
+        let sections = Section::parse(BODY);
+        let headings = sections
+            .iter()
+            .map(|section| section.fmt_heading())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            headings,
+            vec![
+                "Abstract",
+                "1.  Introduction",
+                "2.  Widgets",
+                "Appendix A.  Acknowledgements"
+            ]
+        );
+        assert_eq!(sections[1].children[0].number, "1.1");
+        assert_eq!(
+            sections[1].fmt_toc(),
+            "1.  Introduction\n  1.1.  Terminology"
+        );
+        assert_eq!(
+            Section::find(&sections, "1.1").unwrap().text(),
+            "1.1.  Terminology\n\n   The key words are used."
+        );
+        assert_eq!(
+            Section::find(&sections, "Section 1").unwrap().text(),
+            "1.  Introduction\n\n   Widgets are useful.\n\n1.1.  Terminology\n\n   The key words are used."
+        );
+        assert_eq!(Section::find(&sections, "§2.").unwrap().title, "Widgets");
+        assert_eq!(Section::find(&sections, "appendix a").unwrap().number, "A");
+        assert_eq!(Section::find(&sections, "abstract").unwrap().level(), 1);
+        assert!(Section::find(&sections, "3").is_none());
+        let flat = Section::flatten(&sections);
+        assert_eq!(flat.len(), 5);
+        assert_eq!(flat[2].number, "1.1");
+        assert_eq!(flat[3].paragraphs(), vec!["A widget is a thing."]);
+    }
+
+    #[test]
+    fn test_strip_pages() {
+        let lines = strip_pages(BODY, true);
+        assert!(!lines.iter().any(|line| line.contains("[Page 1]")));
+        assert!(!lines.iter().any(|line| line.starts_with("RFC 9999")));
+        assert!(!lines.windows(2).any(|w| w[0].is_empty() && w[1].is_empty()));
+        let lines = strip_pages(BODY, false);
+        let i = lines
+            .iter()
+            .position(|line| line.ends_with("are used."))
+            .unwrap();
+        assert_eq!(lines[i + 1], "2.  Widgets");
+    }
+
+    #[test]
+    fn test_heading() {
+        assert_eq!(
+            heading("2.6.  Other Definitions"),
+            Some(("2.6".to_string(), "Other Definitions".to_string()))
+        );
+        assert_eq!(
+            heading("A.1.  Examples"),
+            Some(("A.1".to_string(), "Examples".to_string()))
+        );
+        assert_eq!(heading("   2.6.  Other Definitions"), None);
+        assert_eq!(
+            heading("Melnikov, et al.             Standards Track"),
+            None
+        );
+        assert_eq!(heading("x.  lowercase"), None);
+    }
+}
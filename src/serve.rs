@@ -0,0 +1,662 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+
+use pipeliner::Pipeline;
+use serde_json::{json, Map, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::collection::{Collection, TYPES};
+use crate::document::{fmt_id, normalize_id, Document, MetadataAttr, Policy, STATUS};
+use crate::errors::{Error, Result};
+use crate::overlay::Overlay;
+
+const COLUMNS: &[(&str, &str)] = &[
+    ("id", "Document"),
+    ("title", "Title"),
+    ("date", "Date"),
+    ("status", "Status"),
+];
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}\
+th,td{text-align:left;vertical-align:top;padding:.2em .6em;border-bottom:1px solid #ddd}\
+pre{white-space:pre-wrap}\
+.status{color:#b00;font-weight:bold}";
+
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn html(title: &str, content: String) -> Self {
+        Reply {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: format!(
+                "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+                 <title>{}</title><style>{}</style></head>\n<body>\n\
+                 <p><a href=\"/\">rfz</a></p>\n{}</body></html>\n",
+                escape(title),
+                STYLE,
+                content
+            )
+            .into_bytes(),
+        }
+    }
+
+    fn json(value: Value) -> Self {
+        Reply {
+            status: 200,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: &str, api: bool) -> Self {
+        let mut reply = if api {
+            Reply::json(json!({ "error": message }))
+        } else {
+            Reply::html(message, format!("<h1>{}</h1>\n", escape(message)))
+        };
+        reply.status = status;
+        reply
+    }
+
+    fn from_error(err: Error, api: bool) -> Self {
+        match err {
+            Error::DocumentNotFound(message) => Reply::error(404, &message, api),
+            err => Reply::error(500, &err.to_string(), api),
+        }
+    }
+}
+
+pub struct Mirror {
+    overlay: Overlay,
+    files: HashMap<String, Document>,
+    // File names in `files` by the id of the latest version, and by stem.
+    ids: HashMap<String, String>,
+    stems: HashMap<String, String>,
+    latest: Collection,
+}
+
+impl Mirror {
+    pub fn from_dir(dir: PathBuf, jobs: usize, policy: Policy) -> Result<Self> {
+        let overlay = Overlay::from_dir(&dir);
+        let collection = Collection::from_dir(dir)?
+            .with_overlay(&overlay)
+            .with_policy(policy);
+        let mut files = HashMap::new();
+        for doc in collection.with_threads(jobs).map(|doc| {
+            if let Err(e) = doc.ensure_meta() {
+                eprintln!("{}", e);
+            }
+            doc
+        }) {
+            files.insert(file_name(&doc).to_string(), doc);
+        }
+        let latest = files.values().cloned().collect::<Collection>().newest(1);
+        let mut ids = HashMap::new();
+        for doc in &latest {
+            ids.insert(doc.id().to_string(), file_name(doc).to_string());
+        }
+        let mut names = files.keys().collect::<Vec<&String>>();
+        names.sort();
+        let mut stems = HashMap::new();
+        for name in names {
+            if let Some((stem, _)) = name.rsplit_once('.') {
+                stems
+                    .entry(stem.to_string())
+                    .or_insert_with(|| name.to_string());
+            }
+        }
+        Ok(Mirror {
+            overlay,
+            files,
+            ids,
+            stems,
+            latest,
+        })
+    }
+
+    fn route(&self, url: &str) -> Reply {
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path, parse_query(query)),
+            None => (url, Vec::new()),
+        };
+        let path = decode(path);
+        let api = path.starts_with("/api/");
+        let result = match path.trim_start_matches('/').split_once('/') {
+            None if path == "/" => Ok(self.index_page(&query)),
+            Some(("doc", name)) => self.document(name),
+            Some(("meta", id)) => self.meta_page(id),
+            Some(("api", "index")) => Ok(Reply::json(self.api_index(&query))),
+            Some(("api", endpoint)) => match endpoint.split_once('/') {
+                Some(("show", id)) => self.api_show(id).map(Reply::json),
+                Some(("summary", name)) => self.api_summary(name).map(Reply::json),
+                _ => Err(not_found(&path)),
+            },
+            _ => Err(not_found(&path)),
+        };
+        result.unwrap_or_else(|e| Reply::from_error(e, api))
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Document> {
+        if let Some(doc) = self.files.get(name) {
+            return Some(doc);
+        }
+        self.ids
+            .get(&normalize_id(name))
+            .or_else(|| self.stems.get(name))
+            .and_then(|file| self.files.get(file))
+    }
+
+    fn select(&self, query: &[(String, String)]) -> Vec<&Document> {
+        let types = query
+            .iter()
+            .filter(|(key, value)| key == "type" && TYPES.contains(&value.as_str()))
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<&str>>();
+        let types = if types.is_empty() { None } else { Some(types) };
+        let filter = param(query, "q").map(str::to_lowercase);
+        let mut docs = self
+            .latest
+            .filter_types(types)
+            .into_iter()
+            .filter_map(|doc| self.files.get(file_name(&doc)))
+            .filter(|doc| match &filter {
+                Some(filter) => {
+                    doc.id().contains(filter.as_str())
+                        || doc
+                            .title()
+                            .unwrap_or_default()
+                            .to_lowercase()
+                            .contains(filter.as_str())
+                }
+                None => true,
+            })
+            .collect::<Vec<&Document>>();
+        match param(query, "sort") {
+            Some(column @ ("title" | "date" | "status")) => {
+                docs.sort_by_cached_key(|doc| column_value(doc, column).to_lowercase())
+            }
+            _ => docs.sort_by(|a, b| a.id().cmp(b.id())),
+        }
+        docs
+    }
+
+    fn index_page(&self, query: &[(String, String)]) -> Reply {
+        let docs = self.select(query);
+        let filter = param(query, "q").unwrap_or("");
+        let mut content = String::from("<h1>Local document mirror</h1>\n<form action=\"/\">\n");
+        content.push_str(&format!(
+            "<input name=\"q\" value=\"{}\" placeholder=\"Filter by id or title\">\n",
+            escape(filter)
+        ));
+        for t in TYPES {
+            let checked = query.iter().any(|(key, value)| key == "type" && value == t);
+            content.push_str(&format!(
+                "<label><input type=\"checkbox\" name=\"type\" value=\"{}\"{}>{}</label>\n",
+                t,
+                if checked { " checked" } else { "" },
+                t
+            ));
+        }
+        content.push_str("<button>Filter</button>\n</form>\n");
+        content.push_str(&format!("<p>{} documents</p>\n<table>\n<tr>", docs.len()));
+        for (column, label) in COLUMNS {
+            let mut params = query
+                .iter()
+                .filter(|(key, _)| key != "sort")
+                .map(|(key, value)| format!("{}={}", key, encode(value)))
+                .collect::<Vec<String>>();
+            params.push(format!("sort={}", column));
+            content.push_str(&format!(
+                "<th><a href=\"/?{}\">{}</a></th>",
+                escape(&params.join("&")),
+                label
+            ));
+        }
+        content.push_str("</tr>\n");
+        for doc in docs {
+            content.push_str(&format!(
+                "<tr><td><a href=\"/meta/{}\">{}</a></td><td><a href=\"/doc/{}\">{}</a></td>\
+                 <td>{}</td><td>{}</td></tr>\n",
+                encode(doc.id()),
+                escape(&fmt_doc(doc)),
+                encode(file_name(doc)),
+                escape(&column_value(doc, "title")),
+                escape(&column_value(doc, "date")),
+                escape(&column_value(doc, "status")),
+            ));
+        }
+        content.push_str("</table>\n");
+        Reply::html("rfz", content)
+    }
+
+    fn meta_page(&self, id: &str) -> Result<Reply> {
+        let id = normalize_id(id);
+        let docs = self.resolve(&id)?;
+        let mut content = format!("<h1>{}</h1>\n", escape(&fmt_id(&id)));
+        if let Some(members) = self.overlay.members(&id) {
+            content.push_str(&format!(
+                "<p>{}</p>\n",
+                members
+                    .iter()
+                    .map(|member| link_id(member))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        for doc in docs {
+            let meta = doc.meta()?;
+            content.push_str(&format!(
+                "<h2><a href=\"/doc/{}\">{}</a></h2>\n",
+                encode(file_name(doc)),
+                escape(&fmt_doc(doc))
+            ));
+            for (key, label) in STATUS {
+                let ids = meta.values(key);
+                if !ids.is_empty() {
+                    content.push_str(&format!(
+                        "<p class=\"status\">{} {}</p>\n",
+                        label,
+                        ids.iter()
+                            .map(|id| link_id(id))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ));
+                }
+            }
+            content.push_str("<table>\n");
+            let mut keys = meta.keys();
+            keys.sort();
+            for key in keys {
+                let values = meta
+                    .values(key)
+                    .iter()
+                    .map(|value| {
+                        if key.starts_with("Relation.") {
+                            link_id(value)
+                        } else if key.starts_with("Description.") {
+                            format!("<pre>{}</pre>", escape(value))
+                        } else {
+                            escape(value)
+                        }
+                    })
+                    .collect::<Vec<String>>();
+                content.push_str(&format!(
+                    "<tr><th>{}</th><td>{}</td></tr>\n",
+                    escape(key),
+                    values.join("<br>\n")
+                ));
+            }
+            content.push_str("</table>\n");
+        }
+        Ok(Reply::html(&fmt_id(&id), content))
+    }
+
+    fn document(&self, name: &str) -> Result<Reply> {
+        let doc = match self.lookup(name) {
+            Some(doc) => doc,
+            None => return Err(not_found(name)),
+        };
+        let body = match fs::read(doc.path()) {
+            Ok(body) => body,
+            Err(e) => return Err(Error::DocumentParseError(e).in_document(doc.path())),
+        };
+        let file = file_name(doc);
+        let (content_type, body) = if file.ends_with(".html") {
+            let html = String::from_utf8_lossy(&body);
+            (
+                "text/html; charset=utf-8",
+                self.rewrite_links(&html).into_bytes(),
+            )
+        } else if file.ends_with(".xml") {
+            ("application/xml", body)
+        } else {
+            ("text/plain; charset=utf-8", body)
+        };
+        Ok(Reply {
+            status: 200,
+            content_type,
+            body,
+        })
+    }
+
+    fn rewrite_links(&self, html: &str) -> String {
+        const LINK: &str = "href=\"./";
+        let mut output = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find(LINK) {
+            output.push_str(&rest[..start]);
+            rest = &rest[start + LINK.len()..];
+            let end = rest.find('"').unwrap_or(rest.len());
+            let (target, fragment) = match rest[..end].split_once('#') {
+                Some((target, fragment)) => (target, format!("#{}", fragment)),
+                None => (&rest[..end], String::new()),
+            };
+            match self.lookup(target) {
+                Some(doc) => output.push_str(&format!(
+                    "href=\"/doc/{}{}",
+                    encode(file_name(doc)),
+                    fragment
+                )),
+                None => output.push_str(&format!("{}{}", LINK, &rest[..end])),
+            }
+            rest = &rest[end..];
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn resolve(&self, id: &str) -> Result<Vec<&Document>> {
+        let docs = self
+            .latest
+            .resolve(id, &self.overlay)
+            .into_iter()
+            .filter_map(|doc| self.files.get(file_name(&doc)))
+            .collect::<Vec<&Document>>();
+        if docs.is_empty() {
+            return Err(Error::DocumentNotFound(format!(
+                "Failed to find a document matching '{}'",
+                id
+            )));
+        }
+        Ok(docs)
+    }
+
+    fn api_index(&self, query: &[(String, String)]) -> Value {
+        Value::Array(self.select(query).into_iter().map(doc_json).collect())
+    }
+
+    fn api_show(&self, id: &str) -> Result<Value> {
+        let id = normalize_id(id);
+        let docs = self.resolve(&id)?;
+        Ok(json!({
+            "id": id,
+            "members": self.overlay.members(&id),
+            "documents": docs.into_iter().map(doc_json).collect::<Vec<Value>>(),
+        }))
+    }
+
+    fn api_summary(&self, name: &str) -> Result<Value> {
+        match self.files.get(name) {
+            Some(doc) => {
+                doc.meta()?;
+                Ok(doc_json(doc))
+            }
+            None => Err(not_found(name)),
+        }
+    }
+
+    fn respond(&self, request: Request) {
+        let reply = match request.method() {
+            Method::Get | Method::Head => self.route(request.url()),
+            _ => Reply::error(405, "Method not allowed", false),
+        };
+        let header = Header::from_bytes("Content-Type", reply.content_type).unwrap();
+        let response = Response::from_data(reply.body)
+            .with_status_code(reply.status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+fn file_name(doc: &Document) -> &str {
+    doc.path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+}
+
+fn fmt_doc(doc: &Document) -> String {
+    if doc.id().starts_with("draft") {
+        format!("{}-{:02}", doc.id(), -doc.version())
+    } else {
+        fmt_id(doc.id())
+    }
+}
+
+fn column_value(doc: &Document, column: &str) -> String {
+    let meta = match doc.meta() {
+        Ok(meta) => meta,
+        Err(_) => return String::new(),
+    };
+    let keys: &[&str] = match column {
+        "title" => &["Title"],
+        "date" => &["Date.Issued"],
+        "status" => &["Status", "Category"],
+        _ => return doc.id().to_string(),
+    };
+    keys.iter()
+        .find_map(|key| meta.values(key).first().map(|value| value.to_string()))
+        .unwrap_or_default()
+}
+
+fn doc_json(doc: &Document) -> Value {
+    let mut meta = Map::new();
+    if let Ok(metadata) = doc.meta() {
+        for key in metadata.keys() {
+            let value = match metadata.get(key) {
+                Some(MetadataAttr::One(value)) => json!(value),
+                Some(MetadataAttr::Many(values)) => json!(values),
+                None => continue,
+            };
+            meta.insert(key.to_string(), value);
+        }
+    }
+    json!({
+        "id": doc.id(),
+        "version": -doc.version(),
+        "file": file_name(doc),
+        "meta": meta,
+    })
+}
+
+fn link_id(id: &str) -> String {
+    format!(
+        "<a href=\"/meta/{}\">{}</a>",
+        encode(id),
+        escape(&fmt_id(id))
+    )
+}
+
+fn not_found(name: &str) -> Error {
+    Error::DocumentNotFound(format!("Failed to find '{}' in the local mirror", name))
+}
+
+fn param<'a>(query: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    query
+        .iter()
+        .find(|(k, value)| k == key && !value.is_empty())
+        .map(|(_, value)| value.as_str())
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (decode(key), decode(value)),
+            None => (decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => output.push(b' '),
+            b'%' => match bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .map(|hex| u8::from_str_radix(&String::from_utf8_lossy(hex), 16))
+            {
+                Some(Ok(byte)) => {
+                    output.push(byte);
+                    i += 2;
+                }
+                _ => output.push(b'%'),
+            },
+            byte => output.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn run(dir: PathBuf, listen: &str, jobs: usize, policy: Policy) -> Result<()> {
+    let mirror = Mirror::from_dir(dir, jobs, policy)?;
+    let server = match Server::http(listen) {
+        Ok(server) => server,
+        Err(e) => {
+            return Err(Error::ServerError(format!(
+                "Failed to listen on '{}': {}",
+                listen, e
+            )))
+        }
+    };
+    eprintln!("Serving local mirror on http://{}/", listen);
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    mirror.respond(request);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::resource_path;
+
+    fn mirror() -> Result<Mirror> {
+        Mirror::from_dir(resource_path(""), 1, Policy::Strict)
+    }
+
+    fn body(reply: &Reply) -> String {
+        String::from_utf8_lossy(&reply.body).into_owned()
+    }
+
+    #[test]
+    fn test_index_page() -> Result<()> {
+        let mirror = mirror()?;
+        let reply = mirror.route("/?q=bgp&sort=title");
+        assert_eq!(reply.status, 200);
+        let page = body(&reply);
+        assert!(page.contains("<p>1 documents</p>"));
+        assert!(page.contains("href=\"/meta/rfc8212\""));
+        assert!(page.contains("href=\"/doc/rfc8212.xml\""));
+        assert!(!page.contains("href=\"/meta/rfc6468\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_meta_page() -> Result<()> {
+        let mirror = mirror()?;
+        let page = body(&mirror.route("/meta/BCP%2014"));
+        assert!(page.contains("<h1>BCP 14</h1>"));
+        assert!(page.contains("<a href=\"/meta/rfc2119\">RFC 2119</a>"));
+        assert!(page.contains("Relation.Updates"));
+        assert_eq!(mirror.route("/meta/rfc9999").status, 404);
+        Ok(())
+    }
+
+    #[test]
+    fn test_document() -> Result<()> {
+        let mirror = mirror()?;
+        let reply = mirror.route("/doc/draft-ietf-sidrops-rpkimaxlen-04");
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.content_type, "text/html; charset=utf-8");
+        assert_eq!(mirror.route("/doc/rfc8174").content_type, "application/xml");
+        assert_eq!(mirror.route("/doc/..%2FCargo.toml").status, 404);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_links() -> Result<()> {
+        let mirror = mirror()?;
+        let html = "<a href=\"./rfc8212#section-2\">RFC 8212</a> \
+                    <a href=\"./draft-ietf-sidrops-rpkimaxlen-04\">-04</a> \
+                    <a href=\"./rfc3261\">RFC 3261</a>";
+        assert_eq!(
+            mirror.rewrite_links(html),
+            "<a href=\"/doc/rfc8212.xml#section-2\">RFC 8212</a> \
+             <a href=\"/doc/draft-ietf-sidrops-rpkimaxlen-04.html\">-04</a> \
+             <a href=\"./rfc3261\">RFC 3261</a>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_api() -> Result<()> {
+        let mirror = mirror()?;
+        let index: Value =
+            serde_json::from_slice(&mirror.route("/api/index?type=draft").body).unwrap();
+        assert_eq!(index.as_array().unwrap().len(), 2);
+        assert_eq!(index[1]["id"], "draft-ietf-sidrops-rpkimaxlen");
+        assert_eq!(index[1]["version"], 5);
+        let show: Value = serde_json::from_slice(&mirror.route("/api/show/bcp14").body).unwrap();
+        assert_eq!(show["members"], json!(["rfc2119", "rfc8174"]));
+        assert_eq!(
+            show["documents"][0]["meta"]["Relation.IsPartOf"],
+            json!(["bcp14"])
+        );
+        let summary: Value =
+            serde_json::from_slice(&mirror.route("/api/summary/rfc6468.txt").body).unwrap();
+        assert_eq!(summary["file"], "rfc6468.txt");
+        let reply = mirror.route("/api/show/rfc9999");
+        assert_eq!(reply.status, 404);
+        assert_eq!(reply.content_type, "application/json");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("q=sip+message&type=rfc&type=&x"),
+            vec![
+                ("q".to_string(), "sip message".to_string()),
+                ("type".to_string(), "rfc".to_string()),
+                ("type".to_string(), String::new()),
+                ("x".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(decode("BCP%2014%"), "BCP 14%");
+        assert_eq!(decode("%+5x"), "% 5x");
+        assert_eq!(decode("%e2%82%ac"), "\u{20ac}");
+        assert_eq!(encode("a b/c"), "a%20b%2Fc");
+    }
+}
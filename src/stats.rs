@@ -0,0 +1,187 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+use serde_json::{json, Value};
+
+use crate::author::AuthorIndex;
+use crate::collection::Collection;
+use crate::document::{Date, DraftState};
+
+const TOP_AUTHORS: usize = 10;
+
+pub struct Stats {
+    files: usize,
+    size: u64,
+    failures: usize,
+    types: BTreeMap<String, usize>,
+    statuses: BTreeMap<String, usize>,
+    groups: BTreeMap<String, usize>,
+    revisions: BTreeMap<usize, usize>,
+    years: BTreeMap<u16, usize>,
+    authors: Vec<(String, usize)>,
+}
+
+impl Stats {
+    pub fn new(collection: Collection, jobs: usize) -> Self {
+        let files = collection.len();
+        let size = (&collection)
+            .into_iter()
+            .filter_map(|doc| fs::metadata(doc.path()).ok())
+            .map(|meta| meta.len())
+            .sum();
+        let mut versions = HashMap::new();
+        for doc in &collection {
+            if doc.id().starts_with("draft") {
+                versions
+                    .entry(doc.id().to_owned())
+                    .or_insert_with(HashSet::new)
+                    .insert(*doc.version());
+            }
+        }
+        let newest = collection.newest(1);
+        let docs = newest.clone().with_meta(jobs).with_lineage();
+        let mut stats = Stats {
+            files,
+            size,
+            failures: newest.len() - docs.len(),
+            types: BTreeMap::new(),
+            statuses: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            revisions: BTreeMap::new(),
+            years: BTreeMap::new(),
+            authors: Vec::new(),
+        };
+        for doc in &docs {
+            let meta = doc.meta().unwrap();
+            let kind = if doc.id().starts_with("draft") {
+                "draft"
+            } else {
+                "rfc"
+            };
+            *stats.types.entry(kind.to_string()).or_default() += 1;
+            let status = match doc.state() {
+                Some(DraftState::Active) => "Active draft".to_string(),
+                Some(DraftState::Expired) => "Expired draft".to_string(),
+                Some(DraftState::Replaced(_)) => "Replaced draft".to_string(),
+                None => ["Status", "Category"]
+                    .iter()
+                    .find_map(|key| meta.values(key).first().map(|value| value.to_uppercase()))
+                    .unwrap_or_else(|| "UNKNOWN".to_string()),
+            };
+            *stats.statuses.entry(status).or_default() += 1;
+            if let Some(group) = doc.group() {
+                *stats.groups.entry(group.to_owned()).or_default() += 1;
+            }
+            if kind == "draft" {
+                continue;
+            }
+            if let Some(date) = meta
+                .values("Date.Issued")
+                .first()
+                .and_then(|date| Date::parse(date))
+            {
+                *stats.years.entry(date.year).or_default() += 1;
+            }
+        }
+        for revisions in versions.values() {
+            *stats.revisions.entry(revisions.len()).or_default() += 1;
+        }
+        let mut authors = AuthorIndex::from(docs)
+            .into_iter()
+            .map(|entry| (entry.author.fullname.to_owned(), entry.docs.len()))
+            .collect::<Vec<(String, usize)>>();
+        authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        authors.truncate(TOP_AUTHORS);
+        stats.authors = authors;
+        stats
+    }
+
+    pub fn fmt_table(&self) -> String {
+        let mut lines = vec![
+            format!("{:<32}{:>8}", "Files", self.files),
+            format!("{:<32}{:>8}", "Size on disk (KiB)", self.size / 1024),
+            format!("{:<32}{:>8}", "Parse failures (latest only)", self.failures),
+        ];
+        let sections: &[(&str, Vec<(String, usize)>)] = &[
+            ("Documents by type", to_rows(&self.types)),
+            ("Documents by status", to_rows(&self.statuses)),
+            ("Drafts per working group", to_rows(&self.groups)),
+            ("Drafts by number of revisions", to_rows(&self.revisions)),
+            ("RFCs published per year", to_rows(&self.years)),
+            ("Top authors", self.authors.clone()),
+        ];
+        for (heading, rows) in sections {
+            lines.push(String::new());
+            lines.push(heading.to_string());
+            for (key, count) in rows {
+                lines.push(format!("  {:<30}{:>8}", key, count));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "files": self.files,
+            "size": self.size,
+            "failures": self.failures,
+            "types": self.types,
+            "statuses": self.statuses,
+            "groups": self.groups,
+            "revisions": self.revisions,
+            "years": self.years,
+            "authors": self
+                .authors
+                .iter()
+                .map(|(name, count)| json!({ "name": name, "count": count }))
+                .collect::<Vec<Value>>(),
+        })
+    }
+}
+
+fn to_rows<K: ToString>(map: &BTreeMap<K, usize>) -> Vec<(String, usize)> {
+    map.iter()
+        .map(|(key, count)| (key.to_string(), *count))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::errors::Result;
+    use crate::overlay::Overlay;
+    use crate::test::resource_path;
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let path = resource_path("");
+        let overlay = Overlay::from_dir(&path);
+        let collection = Collection::from_dir(path)?.with_overlay(&overlay);
+        let stats = Stats::new(collection, 1).to_json();
+        assert_eq!(stats["files"], 7);
+        assert_eq!(stats["failures"], 1);
+        assert_eq!(stats["types"], json!({ "draft": 1, "rfc": 3 }));
+        assert_eq!(stats["statuses"]["BEST CURRENT PRACTICE"], 1);
+        assert_eq!(stats["statuses"]["Expired draft"], 1);
+        assert_eq!(stats["groups"], json!({ "sidrops": 1 }));
+        assert_eq!(stats["revisions"], json!({ "1": 1, "2": 1 }));
+        assert_eq!(stats["years"], json!({ "2012": 1, "2017": 2 }));
+        assert!(stats["size"].as_u64().unwrap() > 0);
+        assert_eq!(
+            stats["authors"][0],
+            json!({ "name": "Job Snijders", "count": 2 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_table() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let table = Stats::new(collection, 1).fmt_table();
+        assert!(table.contains("Parse failures (latest only)           1"));
+        assert!(table.contains("Drafts by number of revisions\n  1 "));
+        assert!(table.contains("Drafts per working group\n  sidrops"));
+        Ok(())
+    }
+}
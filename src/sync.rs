@@ -0,0 +1,171 @@
+use std::io::{self, stderr, BufReader, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::errors::{Error, Result};
+
+const BAR_WIDTH: usize = 30;
+
+pub fn run(mut proc: Command, progress: bool) -> Result<()> {
+    if !progress {
+        return check(proc.status());
+    }
+    proc.arg("--info=progress2")
+        .arg("--no-inc-recursive")
+        .stdout(Stdio::piped());
+    let mut child = match proc.spawn() {
+        Ok(child) => child,
+        Err(e) => return Err(Error::SyncError(e)),
+    };
+    let mut reader = BufReader::new(child.stdout.take().unwrap());
+    let mut line = Vec::new();
+    let mut byte = [0; 1];
+    while let Ok(1) = reader.read(&mut byte) {
+        if byte[0] != b'\r' && byte[0] != b'\n' {
+            line.push(byte[0]);
+            continue;
+        }
+        if let Some(progress) = Progress::parse(&String::from_utf8_lossy(&line)) {
+            eprint!("\r{}", progress.fmt_bar());
+            stderr().flush().ok();
+        }
+        line.clear();
+    }
+    eprintln!();
+    check(child.wait())
+}
+
+pub fn check(status: io::Result<ExitStatus>) -> Result<()> {
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => return Err(Error::SyncError(e)),
+    };
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code @ 23) | Some(code @ 24) => Err(Error::PartialFailure(format!(
+            "rsync exited with code {}: {}",
+            code,
+            describe(code)
+        ))),
+        Some(code) => Err(Error::SyncError(io::Error::other(format!(
+            "rsync exited with code {}: {}",
+            code,
+            describe(code)
+        )))),
+        None => Err(Error::SyncError(io::Error::other(format!(
+            "rsync was terminated ({})",
+            status
+        )))),
+    }
+}
+
+fn describe(code: i32) -> &'static str {
+    match code {
+        1 => "syntax or usage error",
+        2 => "protocol incompatibility",
+        3 => "errors selecting input/output files or directories",
+        4 => "requested action not supported by the server",
+        5 => "error starting client-server protocol (check the remote and your access to it)",
+        6 => "daemon unable to append to log-file",
+        10 => "error in socket I/O (check network connectivity)",
+        11 => "error in file I/O (check free disk space and permissions)",
+        12 => "error in rsync protocol data stream",
+        13 => "errors with program diagnostics",
+        14 => "error in IPC code",
+        20 => "received SIGUSR1 or SIGINT",
+        21 => "some error returned by waitpid()",
+        22 => "error allocating core memory buffers",
+        23 => "partial transfer due to error",
+        24 => "partial transfer due to vanished source files",
+        25 => "the --max-delete limit stopped deletions",
+        30 => "timeout in data send/receive",
+        35 => "timeout waiting for daemon connection",
+        127 => "rsync command not found",
+        _ => "unknown error",
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Progress {
+    bytes: u64,
+    percent: u8,
+    rate: String,
+    eta: String,
+}
+
+impl Progress {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let bytes = fields.next()?.replace(',', "").parse().ok()?;
+        let percent = fields.next()?.strip_suffix('%')?.parse().ok()?;
+        let rate = fields.next()?.to_string();
+        let eta = fields.next()?.to_string();
+        Some(Progress {
+            bytes,
+            percent,
+            rate,
+            eta,
+        })
+    }
+
+    fn fmt_bar(&self) -> String {
+        let filled = BAR_WIDTH * usize::from(self.percent.min(100)) / 100;
+        format!(
+            "[{}{}] {:>3}% {:>10} MiB {:>12} ETA {}",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.percent,
+            self.bytes / (1024 * 1024),
+            self.rate,
+            self.eta
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fake_rsync(script: &str) -> Command {
+        let mut proc = Command::new("/bin/sh");
+        proc.arg("-c").arg(script).arg("rsync");
+        proc
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        let progress = Progress::parse(
+            "    104,857,600  45%    1.23MB/s    0:00:12 (xfr#12, to-chk=100/2000)",
+        )
+        .unwrap();
+        assert_eq!(progress.bytes, 104_857_600);
+        assert_eq!(progress.percent, 45);
+        assert_eq!(progress.rate, "1.23MB/s");
+        assert_eq!(progress.eta, "0:00:12");
+        assert!(progress
+            .fmt_bar()
+            .starts_with("[#############-----------------]  45%"));
+        assert_eq!(Progress::parse("receiving incremental file list"), None);
+        assert_eq!(Progress::parse(""), None);
+    }
+
+    #[test]
+    fn test_exit_status() {
+        assert!(run(fake_rsync("exit 0"), false).is_ok());
+        assert!(matches!(
+            run(fake_rsync("exit 24"), false),
+            Err(Error::PartialFailure(_))
+        ));
+        match run(fake_rsync("exit 5"), false) {
+            Err(e @ Error::SyncError(_)) => assert!(e.to_string().contains("code 5")),
+            _ => panic!("Expected sync to fail"),
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let script =
+            "printf '  1,024  50%%  1.00kB/s  0:00:01\\r  2,048 100%%  1.00kB/s  0:00:00\\n'";
+        assert!(run(fake_rsync(script), true).is_ok());
+        assert!(run(fake_rsync("exit 30"), true).is_err());
+    }
+}
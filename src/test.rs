@@ -6,3 +6,10 @@ pub fn resource_path(name: &str) -> PathBuf {
     d.push(name);
     d
 }
+
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rfz-test-{}-{}", name, std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
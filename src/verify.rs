@@ -0,0 +1,241 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pipeliner::Pipeline;
+
+use crate::annotate::ANNOTATIONS;
+use crate::document::Document;
+use crate::errata::ERRATA;
+use crate::errors::{Error, Result};
+use crate::history::{HISTORY, NO_HISTORY};
+use crate::overlay::RFC_INDEX;
+
+const REQUIRED: &[&str] = &["Identifier", "Title"];
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    Empty,
+    Truncated,
+    Unreadable(String),
+    ParseError(String),
+    MissingMetadata(String),
+    UnknownFile,
+}
+
+impl Problem {
+    pub fn describe(&self) -> String {
+        match self {
+            Problem::Empty => "empty file".to_string(),
+            Problem::Truncated => "truncated document".to_string(),
+            Problem::Unreadable(e) => format!("failed to read: {}", e),
+            Problem::ParseError(e) => format!("failed to parse: {}", e),
+            Problem::MissingMetadata(key) => format!("missing metadata '{}'", key),
+            Problem::UnknownFile => "unrecognised file name".to_string(),
+        }
+    }
+
+    fn refetch(&self) -> bool {
+        matches!(
+            self,
+            Problem::Empty | Problem::Truncated | Problem::Unreadable(_)
+        )
+    }
+}
+
+pub struct Report {
+    pub checked: usize,
+    pub problems: Vec<(PathBuf, Problem)>,
+}
+
+impl Report {
+    pub fn from_dir(dir: PathBuf, jobs: usize) -> Result<Self> {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => return Err(Error::DirectoryReadError(e)),
+        };
+        let mut docs = Vec::new();
+        let mut problems = Vec::new();
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let name = path.file_name().unwrap_or_default();
+            if !path.is_file()
+                || name == OsStr::new(RFC_INDEX)
+                || name == OsStr::new(ERRATA)
+                || name == OsStr::new(ANNOTATIONS)
+                || name == OsStr::new(HISTORY)
+                || name == OsStr::new(NO_HISTORY)
+                || name.to_string_lossy().starts_with('.')
+            {
+                continue;
+            }
+            match Document::from_path(path.clone()) {
+                Some(Ok(doc)) => docs.push(doc),
+                Some(Err(e)) => problems.push((path, Problem::ParseError(e.kind().to_string()))),
+                None => problems.push((path, Problem::UnknownFile)),
+            }
+        }
+        let checked = docs.len() + problems.len();
+        for result in docs.with_threads(jobs).map(check) {
+            problems.extend(result);
+        }
+        problems.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Report { checked, problems })
+    }
+
+    pub fn refetch(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut paths = self
+            .problems
+            .iter()
+            .filter(|(_, problem)| problem.refetch())
+            .filter_map(|(path, _)| path.strip_prefix(dir).ok())
+            .map(|path| path.to_path_buf())
+            .collect::<Vec<PathBuf>>();
+        paths.dedup();
+        paths
+    }
+
+    pub fn fmt_report(&self) -> String {
+        let mut lines = self
+            .problems
+            .iter()
+            .map(|(path, problem)| format!("{}: {}", path.to_string_lossy(), problem.describe()))
+            .collect::<Vec<String>>();
+        lines.push(format!(
+            "{} files checked, {} problems found",
+            self.checked,
+            self.problems.len()
+        ));
+        lines.join("\n")
+    }
+}
+
+fn check(doc: Document) -> Vec<(PathBuf, Problem)> {
+    let path = doc.path().to_owned();
+    let content = match fs::read(&path) {
+        Ok(content) => content,
+        Err(e) => return vec![(path, Problem::Unreadable(e.to_string()))],
+    };
+    if content.is_empty() {
+        return vec![(path, Problem::Empty)];
+    }
+    let complete = match path.extension().and_then(OsStr::to_str) {
+        Some("html") => is_complete_html(&content),
+        Some("xml") => is_complete_xml(&content),
+        Some("txt") => is_complete_text(&content),
+        _ => true,
+    };
+    if !complete {
+        return vec![(path, Problem::Truncated)];
+    }
+    let meta = match doc.meta() {
+        Ok(meta) => meta,
+        Err(e) => return vec![(path, Problem::ParseError(e.kind().to_string()))],
+    };
+    REQUIRED
+        .iter()
+        .filter(|key| meta.get(key).is_none())
+        .map(|key| (path.to_owned(), Problem::MissingMetadata(key.to_string())))
+        .collect()
+}
+
+fn is_complete_html(content: &[u8]) -> bool {
+    let tail = &content[content.len().saturating_sub(1024)..];
+    String::from_utf8_lossy(tail)
+        .to_lowercase()
+        .contains("</html>")
+}
+
+fn is_complete_xml(content: &[u8]) -> bool {
+    let tail = &content[content.len().saturating_sub(1024)..];
+    String::from_utf8_lossy(tail).contains("</rfc>")
+}
+
+// Plaintext documents have no closing tag, but always end with a newline,
+// possibly followed by a form feed.
+fn is_complete_text(content: &[u8]) -> bool {
+    let end = content.iter().rposition(|&b| b != b'\x0c');
+    matches!(end, Some(i) if content[i] == b'\n')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::test::{resource_path, scratch_dir};
+
+    #[test]
+    fn test_verify_resources() -> Result<()> {
+        let dir = resource_path("");
+        let report = Report::from_dir(dir.to_owned(), 1)?;
+        assert_eq!(report.checked, 7);
+        assert_eq!(report.problems.len(), 1);
+        assert!(matches!(
+            &report.problems[0],
+            (path, Problem::ParseError(e))
+                if path.ends_with("draft-duplicates-00.html") && e.contains("duplicate attribute")
+        ));
+        assert!(report.refetch(&dir).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_refetch() -> Result<()> {
+        let dir = scratch_dir("verify-refetch");
+        fs::write(dir.join("rfc1.html"), "").unwrap();
+        fs::write(dir.join("rfc2.html"), "<html><body><pre>Network").unwrap();
+        fs::write(dir.join("rfc3.xml"), "<rfc><front><title>Net").unwrap();
+        fs::write(dir.join("rfc4.txt"), "Network Working Group").unwrap();
+        fs::copy(
+            resource_path("draft-duplicates-00.html"),
+            dir.join("draft-duplicates-00.html"),
+        )
+        .unwrap();
+        let report = Report::from_dir(dir.to_owned(), 1)?;
+        assert_eq!(report.problems.len(), 5);
+        assert_eq!(
+            report.refetch(&dir),
+            vec![
+                PathBuf::from("rfc1.html"),
+                PathBuf::from("rfc2.html"),
+                PathBuf::from("rfc3.xml"),
+                PathBuf::from("rfc4.txt"),
+            ]
+        );
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_no_history() -> Result<()> {
+        let dir = scratch_dir("verify-no-history");
+        fs::copy(resource_path("rfc6468.html"), dir.join("rfc6468.html")).unwrap();
+        fs::write(dir.join(HISTORY), "").unwrap();
+        fs::write(dir.join(NO_HISTORY), "").unwrap();
+        let report = Report::from_dir(dir.to_owned(), 1)?;
+        assert_eq!(report.checked, 1);
+        assert!(report.problems.is_empty());
+        fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_complete_html() {
+        assert!(is_complete_html(b"<html><body></body></HTML>\n"));
+        assert!(!is_complete_html(b"<html><body><pre>Network Working"));
+    }
+
+    #[test]
+    fn test_is_complete_xml() {
+        assert!(is_complete_xml(b"<rfc><back></back></rfc>\n"));
+        assert!(!is_complete_xml(b"<rfc><front><title>Network"));
+    }
+
+    #[test]
+    fn test_is_complete_text() {
+        assert!(is_complete_text(b"Author [Page 10]\n"));
+        assert!(is_complete_text(b"Author [Page 10]\n\x0c"));
+        assert!(!is_complete_text(b"Network Working"));
+        assert!(!is_complete_text(b"\x0c"));
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use crate::collection::Collection;
+use crate::document::{parse_draft_name, Document, DraftState, Stream};
+
+#[derive(Debug)]
+pub struct Group {
+    pub name: String,
+    pub stream: Stream,
+    pub drafts: Vec<Document>,
+    pub rfcs: Vec<Document>,
+}
+
+impl Group {
+    pub fn active(&self) -> usize {
+        self.drafts
+            .iter()
+            .filter(|doc| doc.state() == Some(DraftState::Active))
+            .count()
+    }
+}
+
+pub struct GroupIndex(BTreeMap<String, Group>);
+
+impl GroupIndex {
+    pub fn new(collection: Collection, jobs: usize) -> Self {
+        let docs = collection.newest(1).with_meta(jobs).with_lineage();
+        let mut groups = BTreeMap::new();
+        for doc in docs {
+            if let (Some(stream), Some(name)) = (doc.stream(), doc.group()) {
+                entry(&mut groups, stream, name).drafts.push(doc);
+                continue;
+            }
+            let lineage = match doc.meta() {
+                Ok(meta) => meta.values("Relation.Replaces"),
+                Err(_) => continue,
+            };
+            for draft in lineage {
+                if let Some((stream, Some(name), _)) = parse_draft_name(draft) {
+                    let group = entry(&mut groups, stream, &name);
+                    if !group.rfcs.iter().any(|rfc| rfc.id() == doc.id()) {
+                        group.rfcs.push(doc.clone());
+                    }
+                }
+            }
+        }
+        for group in groups.values_mut() {
+            group.drafts.sort_by(|a, b| a.id().cmp(b.id()));
+            group.rfcs.sort_by(|a, b| a.id().cmp(b.id()));
+        }
+        GroupIndex(groups)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Group> {
+        self.0.get(&name.trim().to_lowercase())
+    }
+}
+
+impl<'a> IntoIterator for &'a GroupIndex {
+    type Item = &'a Group;
+    type IntoIter = std::collections::btree_map::Values<'a, String, Group>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.values()
+    }
+}
+
+fn entry<'a>(groups: &'a mut BTreeMap<String, Group>, stream: Stream, name: &str) -> &'a mut Group {
+    groups.entry(name.to_lowercase()).or_insert_with(|| Group {
+        name: name.to_lowercase(),
+        stream,
+        drafts: Vec::new(),
+        rfcs: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::errors::Result;
+    use crate::test::resource_path;
+
+    #[test]
+    fn test_group_index() -> Result<()> {
+        let collection = Collection::from_dir(resource_path(""))?;
+        let index = GroupIndex::new(collection, 1);
+        assert_eq!(
+            index
+                .into_iter()
+                .map(|group| group.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["grow", "sidrops"]
+        );
+        let sidrops = index.get("SIDROPS").unwrap();
+        assert_eq!(sidrops.stream, Stream::Ietf);
+        assert_eq!(sidrops.drafts.len(), 1);
+        assert_eq!(sidrops.drafts[0].version(), &-5);
+        assert_eq!(sidrops.drafts[0].state(), Some(DraftState::Expired));
+        assert_eq!(sidrops.active(), 0);
+        assert!(sidrops.rfcs.is_empty());
+        let grow = index.get("grow").unwrap();
+        assert!(grow.drafts.is_empty());
+        assert_eq!(grow.rfcs[0].id(), "rfc8212");
+        assert!(index.get("idr").is_none());
+        Ok(())
+    }
+}